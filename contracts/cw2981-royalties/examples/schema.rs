@@ -1,11 +1,12 @@
 use cosmwasm_schema::write_api;
 
-use cw2981_royalties::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw2981_royalties::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        migrate: MigrateMsg,
     }
 }
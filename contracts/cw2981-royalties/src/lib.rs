@@ -7,7 +7,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{to_binary, Empty};
 use cw2::set_contract_version;
 use cw721_base::Cw721Contract;
-pub use cw721_base::{ContractError, InstantiateMsg, MintMsg, MinterResponse};
+pub use cw721_base::{ContractError, InstantiateMsg, MigrateMsg, MintMsg, MinterResponse};
 
 use crate::msg::Cw2981QueryMsg;
 
@@ -96,6 +96,11 @@ pub mod entry {
             _ => Cw2981Contract::default().query(deps, env, msg),
         }
     }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        Cw2981Contract::default().migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+    }
 }
 
 #[cfg(test)]
@@ -120,6 +125,15 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            track_ownership_history: None,
+            default_limit: None,
+            max_limit: None,
+            token_id_sequence: None,
+            collection_info: None,
+            max_approvals_per_token: None,
+            token_id_rules: None,
+            enable_clawback: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
@@ -152,6 +166,15 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            track_ownership_history: None,
+            default_limit: None,
+            max_limit: None,
+            token_id_sequence: None,
+            collection_info: None,
+            max_approvals_per_token: None,
+            token_id_rules: None,
+            enable_clawback: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
@@ -193,6 +216,15 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            track_ownership_history: None,
+            default_limit: None,
+            max_limit: None,
+            token_id_sequence: None,
+            collection_info: None,
+            max_approvals_per_token: None,
+            token_id_rules: None,
+            enable_clawback: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
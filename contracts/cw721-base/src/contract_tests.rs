@@ -1,14 +1,26 @@
 #![cfg(test)]
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_binary, to_binary, CosmosMsg, DepsMut, Empty, Response, WasmMsg};
+use std::marker::PhantomData;
+
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockQuerier};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, CosmosMsg, Decimal, DepsMut, Empty, QuerierWrapper, Response,
+    SystemResult, WasmMsg, WasmQuery,
+};
 
 use cw721::{
     Approval, ApprovalResponse, ContractInfoResponse, Cw721Query, Cw721ReceiveMsg, Expiration,
-    NftInfoResponse, OperatorsResponse, OwnerOfResponse,
+    NftInfoResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
 };
 
 use crate::{
-    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MintMsg, QueryMsg,
+    helpers, ApprovedToken, ApprovedTokensResponse, CollectionInfo, CollectionRoyaltyInfo,
+    ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, IsOperatorResponse,
+    MigrateMsg, MintMsg, MintNextResponse, MintQuotaResponse, NextTokenIdResponse, OperatorGrant,
+    OperatorGrantsResponse, OperatorScope, OperatorWithScope, OperatorsWithScopeResponse,
+    OwnershipAction, OwnershipHistoryResponse, OwnershipRecord, PauseStateResponse,
+    PendingMinterResponse, QueryLimitsResponse, QueryMsg, Role, SortOrder, SupplyResponse,
+    TokenIdRules, TokenIdRulesResponse, TokenIdSequence, TokenWithInfo, TokensWithInfoResponse,
+    UserOfResponse, CONTRACT_NAME as BASE_CONTRACT_NAME, CONTRACT_VERSION,
 };
 
 const MINTER: &str = "merlin";
@@ -21,6 +33,15 @@ fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty,
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
     };
     let info = mock_info("creator", &[]);
     let res = contract.instantiate(deps, mock_env(), info, msg).unwrap();
@@ -37,6 +58,15 @@ fn proper_instantiation() {
         name: CONTRACT_NAME.to_string(),
         symbol: SYMBOL.to_string(),
         minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
     };
     let info = mock_info("creator", &[]);
 
@@ -48,7 +78,7 @@ fn proper_instantiation() {
 
     // it worked, let's query the state
     let res = contract.minter(deps.as_ref()).unwrap();
-    assert_eq!(MINTER, res.minter);
+    assert_eq!(Some(MINTER.to_string()), res.minter);
     let info = contract.contract_info(deps.as_ref()).unwrap();
     assert_eq!(
         info,
@@ -66,6 +96,88 @@ fn proper_instantiation() {
     assert_eq!(0, tokens.tokens.len());
 }
 
+#[test]
+fn migrating() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // migrating to the same version only rewrites the stored version
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::default(),
+            BASE_CONTRACT_NAME,
+            CONTRACT_VERSION,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", CONTRACT_VERSION)
+            .add_attribute("to_version", CONTRACT_VERSION)
+    );
+
+    // older versions are upgraded
+    cw2::set_contract_version(deps.as_mut().storage, BASE_CONTRACT_NAME, "0.15.0").unwrap();
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::default(),
+            BASE_CONTRACT_NAME,
+            CONTRACT_VERSION,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", "0.15.0")
+            .add_attribute("to_version", CONTRACT_VERSION)
+            .add_attribute("backfill", "complete")
+    );
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, CONTRACT_VERSION);
+
+    // cannot go back to an older version
+    cw2::set_contract_version(deps.as_mut().storage, BASE_CONTRACT_NAME, "999.0.0").unwrap();
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::default(),
+            BASE_CONTRACT_NAME,
+            CONTRACT_VERSION,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotDowngrade {
+            previous_version: String::from("999.0.0"),
+            new_version: CONTRACT_VERSION.to_string(),
+        }
+    );
+
+    // cannot migrate from another contract
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::default(),
+            "crates.io:cw721-other",
+            CONTRACT_VERSION,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::WrongContract {
+            previous_contract: BASE_CONTRACT_NAME.to_string(),
+        }
+    );
+}
+
 #[test]
 fn minting() {
     let mut deps = mock_dependencies();
@@ -146,316 +258,2919 @@ fn minting() {
 }
 
 #[test]
-fn burning() {
+fn minting_up_to_max_supply() {
     let mut deps = mock_dependencies();
-    let contract = setup_contract(deps.as_mut());
-
-    let token_id = "petrify".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
 
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
-        owner: MINTER.to_string(),
-        token_uri: Some(token_uri),
-        extension: None,
-    });
+    // a zero max supply makes no sense
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: Some(0),
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    let info = mock_info("creator", &[]);
+    contract
+        .instantiate(deps.as_mut(), mock_env(), info.clone(), msg)
+        .unwrap_err();
 
-    let burn_msg = ExecuteMsg::Burn { token_id };
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: Some(3),
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), info, msg)
+        .unwrap();
+    let supply = contract.supply(deps.as_ref()).unwrap();
+    assert_eq!(
+        supply,
+        SupplyResponse {
+            count: 0,
+            minted: 0,
+            max_supply: Some(3),
+            remaining: Some(3),
+        }
+    );
 
-    // mint some NFT
-    let allowed = mock_info(MINTER, &[]);
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg)
+    let mint_msg = |token_id: &str| MintMsg::<Extension> {
+        token_id: token_id.to_string(),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+    };
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(mint_msg("grow")),
+        )
         .unwrap();
 
-    // random not allowed to burn
-    let random = mock_info("random", &[]);
+    // a batch going over the limit is rejected as a whole
     let err = contract
-        .execute(deps.as_mut(), mock_env(), random, burn_msg.clone())
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint_msg("melt"), mint_msg("sing"), mint_msg("petrify")],
+            },
+        )
         .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached {});
 
-    assert_eq!(err, ContractError::Unauthorized {});
-
-    let _ = contract
-        .execute(deps.as_mut(), mock_env(), allowed, burn_msg)
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![mint_msg("melt"), mint_msg("sing")],
+            },
+        )
         .unwrap();
+    let supply = contract.supply(deps.as_ref()).unwrap();
+    assert_eq!(supply.remaining, Some(0));
 
-    // ensure num tokens decreases
-    let count = contract.num_tokens(deps.as_ref()).unwrap();
-    assert_eq!(0, count.count);
-
-    // trying to get nft returns error
-    let _ = contract
-        .nft_info(deps.as_ref(), "petrify".to_string())
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(mint_msg("petrify")),
+        )
         .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached {});
 
-    // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-    assert!(tokens.tokens.is_empty());
+    // burning does not free up supply
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("medusa", &[]),
+            ExecuteMsg::Burn {
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::Mint(mint_msg("petrify")),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MaxSupplyReached {});
+    let supply = contract.supply(deps.as_ref()).unwrap();
+    assert_eq!(
+        supply,
+        SupplyResponse {
+            count: 2,
+            minted: 3,
+            max_supply: Some(3),
+            remaining: Some(0),
+        }
+    );
 }
 
 #[test]
-fn transferring_nft() {
+fn freezing_minting() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
 
-    // Mint a token
-    let token_id = "melt".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
-        extension: None,
-    });
-
+    let mint_msg = |token_id: &str| {
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: None,
+        })
+    };
     let minter = mock_info(MINTER, &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("grow"))
         .unwrap();
+    assert!(!contract.minting_frozen(deps.as_ref()).unwrap().frozen);
 
-    // random cannot transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = ExecuteMsg::TransferNft {
-        recipient: String::from("random"),
-        token_id: token_id.clone(),
-    };
-
+    // random cannot freeze minting
     let err = contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::FreezeMinting {},
+        )
         .unwrap_err();
     assert_eq!(err, ContractError::Unauthorized {});
 
-    // owner can
-    let random = mock_info("venus", &[]);
-    let transfer_msg = ExecuteMsg::TransferNft {
-        recipient: String::from("random"),
-        token_id: token_id.clone(),
-    };
-
     let res = contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::FreezeMinting {},
+        )
         .unwrap();
-
     assert_eq!(
         res,
         Response::new()
-            .add_attribute("action", "transfer_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "random")
-            .add_attribute("token_id", token_id)
+            .add_attribute("action", "freeze_minting")
+            .add_attribute("sender", MINTER)
     );
+    assert!(contract.minting_frozen(deps.as_ref()).unwrap().frozen);
+
+    // no more tokens, ever, not even after handing over the minter role
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("melt"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::MintingFrozen {});
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::ProposeMinterOwnership {
+                new_minter: String::from("merlin2"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let new_minter = mock_info("merlin2", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            new_minter.clone(),
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            new_minter,
+            ExecuteMsg::BatchMint {
+                mints: vec![MintMsg::<Extension> {
+                    token_id: "melt".to_string(),
+                    owner: String::from("medusa"),
+                    token_uri: None,
+                    extension: None,
+                }],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MintingFrozen {});
+
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(1, count.count);
 }
 
 #[test]
-fn sending_nft() {
+fn pausing() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
 
-    // Mint a token
-    let token_id = "melt".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
-        extension: None,
-    });
-
     let minter = mock_info(MINTER, &[]);
+    let owner = mock_info("medusa", &[]);
+    let mint_msg = |token_id: &str| {
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: None,
+        })
+    };
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("grow"))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::Approve {
+                spender: String::from("random"),
+                token_id: "grow".to_string(),
+                expires: None,
+            },
+        )
         .unwrap();
 
-    let msg = to_binary("You now have the melting power").unwrap();
-    let target = String::from("another_contract");
-    let send_msg = ExecuteMsg::SendNft {
-        contract: target.clone(),
-        token_id: token_id.clone(),
-        msg: msg.clone(),
-    };
+    // nothing is paused by default
+    let pause_state = contract.pause_state_info(deps.as_ref()).unwrap();
+    assert_eq!(
+        pause_state,
+        PauseStateResponse {
+            mint: false,
+            transfer: false,
+            approve: false,
+            burn: false,
+        }
+    );
 
-    let random = mock_info("random", &[]);
+    // only the minter can pause
+    let pause_all = ExecuteMsg::SetPauseState {
+        mint: true,
+        transfer: true,
+        approve: true,
+        burn: true,
+    };
     let err = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            pause_all.clone(),
+        )
         .unwrap_err();
     assert_eq!(err, ContractError::Unauthorized {});
 
-    // but owner can
-    let random = mock_info("venus", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), pause_all)
         .unwrap();
 
-    let payload = Cw721ReceiveMsg {
-        sender: String::from("venus"),
-        token_id: token_id.clone(),
-        msg,
-    };
-    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
-    // ensure expected serializes as we think it should
-    match &expected {
-        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
-            assert_eq!(contract_addr, &target)
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("melt"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            action: "mint".to_string()
         }
-        m => panic!("Unexpected message type: {:?}", m),
-    }
-    // and make sure this is the request sent by the contract
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("venus"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap_err();
     assert_eq!(
-        res,
-        Response::new()
-            .add_message(expected)
-            .add_attribute("action", "send_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "another_contract")
-            .add_attribute("token_id", token_id)
+        err,
+        ContractError::Paused {
+            action: "transfer".to_string()
+        }
     );
-}
-
-#[test]
-fn approving_revoking() {
-    let mut deps = mock_dependencies();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("random"),
+                expires: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            action: "approve".to_string()
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["grow".to_string()],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            action: "burn".to_string()
+        }
+    );
+
+    // revoking still works while approvals are paused
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::Revoke {
+                spender: String::from("random"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+
+    // resume transfers only
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::SetPauseState {
+                mint: true,
+                transfer: false,
+                approve: true,
+                burn: true,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "set_pause_state")
+            .add_attribute("sender", MINTER)
+            .add_attribute("mint", "true")
+            .add_attribute("transfer", "false")
+            .add_attribute("approve", "true")
+            .add_attribute("burn", "true")
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::TransferNft {
+                recipient: String::from("venus"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    let owner_of = contract
+        .owner_of(deps.as_ref(), mock_env(), "grow".to_string(), false)
+        .unwrap();
+    assert_eq!(owner_of.owner, "venus");
+}
+
+#[test]
+fn batch_minting() {
+    let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
 
-    // Mint a token
-    let token_id = "grow".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
+    let mints: Vec<_> = ["grow", "melt", "sing"]
+        .iter()
+        .map(|token_id| MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        })
+        .collect();
+    let batch_mint_msg = ExecuteMsg::BatchMint {
+        mints: mints.clone(),
+    };
 
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri),
+    // random cannot mint
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, batch_mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // minter can mint them all at once
+    let minter = mock_info(MINTER, &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), batch_mint_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", MINTER)
+            .add_attribute("owner", "demeter")
+            .add_attribute("token_id", "grow")
+            .add_attribute("owner", "demeter")
+            .add_attribute("token_id", "melt")
+            .add_attribute("owner", "demeter")
+            .add_attribute("token_id", "sing")
+    );
+
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(3, count.count);
+    let tokens = contract
+        .tokens(deps.as_ref(), String::from("demeter"), None, None)
+        .unwrap();
+    assert_eq!(vec!["grow", "melt", "sing"], tokens.tokens);
+
+    // a batch is rejected if a token_id was already minted...
+    let batch_mint_msg = ExecuteMsg::BatchMint {
+        mints: vec![
+            MintMsg::<Extension> {
+                token_id: "petrify".to_string(),
+                owner: String::from("medusa"),
+                token_uri: None,
+                extension: None,
+            },
+            mints[0].clone(),
+        ],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), batch_mint_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Claimed {});
+
+    // ...or appears twice in the same batch
+    let duplicate = MintMsg::<Extension> {
+        token_id: "harden".to_string(),
+        owner: String::from("medusa"),
+        token_uri: None,
         extension: None,
-    });
+    };
+    let batch_mint_msg = ExecuteMsg::BatchMint {
+        mints: vec![duplicate.clone(), duplicate],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, batch_mint_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Claimed {});
+}
+
+#[test]
+fn minting_next_token_id() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: Some(TokenIdSequence {
+            start: 1,
+            prefix: Some(String::from("card-")),
+        }),
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    let next = contract.next_token_id_info(deps.as_ref()).unwrap();
+    assert_eq!(
+        next,
+        NextTokenIdResponse {
+            token_id: String::from("card-1")
+        }
+    );
+
+    let mint_next_msg = ExecuteMsg::MintNext {
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+    };
+
+    // random cannot mint
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            mint_next_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
 
+    // an explicitly minted id is skipped by the sequence
     let minter = mock_info(MINTER, &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: String::from("card-2"),
+                owner: String::from("medusa"),
+                token_uri: None,
+                extension: None,
+            }),
+        )
         .unwrap();
 
-    // token owner shows in approval query
     let res = contract
-        .approval(
-            deps.as_ref(),
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            token_id.clone(),
-            String::from("demeter"),
-            false,
+            minter.clone(),
+            mint_next_msg.clone(),
         )
         .unwrap();
     assert_eq!(
         res,
-        ApprovalResponse {
-            approval: Approval {
-                spender: String::from("demeter"),
-                expires: Expiration::Never {}
+        Response::new()
+            .set_data(
+                to_binary(&MintNextResponse {
+                    token_id: String::from("card-1")
+                })
+                .unwrap()
+            )
+            .add_attribute("action", "mint")
+            .add_attribute("minter", MINTER)
+            .add_attribute("owner", "medusa")
+            .add_attribute("token_id", "card-1")
+    );
+    let next = contract.next_token_id_info(deps.as_ref()).unwrap();
+    assert_eq!(next.token_id, "card-3");
+
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_next_msg)
+        .unwrap();
+    let minted: MintNextResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(minted.token_id, "card-3");
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), minted.token_id, false)
+        .unwrap();
+    assert_eq!(owner.owner, "medusa");
+
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(count.count, 3);
+    let next = contract.next_token_id_info(deps.as_ref()).unwrap();
+    assert_eq!(next.token_id, "card-4");
+}
+
+#[test]
+fn validating_token_ids() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let rules = TokenIdRules {
+        max_length: Some(8),
+        allowed_symbols: Some("-".to_string()),
+        numeric_only: false,
+    };
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: Some(TokenIdSequence {
+            start: 1,
+            prefix: Some(String::from("card-")),
+        }),
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: Some(TokenIdRules {
+            numeric_only: true,
+            ..rules.clone()
+        }),
+        enable_clawback: None,
+    };
+    let info = mock_info("creator", &[]);
+
+    // the sequence must produce valid token ids
+    contract
+        .instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone())
+        .unwrap_err();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InstantiateMsg {
+                token_id_rules: Some(rules.clone()),
+                enable_clawback: None,
+                ..msg
+            },
+        )
+        .unwrap();
+    let res: TokenIdRulesResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::TokenIdRules {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.rules, Some(rules));
+
+    let minter = mock_info(MINTER, &[]);
+    let mint = |token_id: &str| {
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: None,
+        })
+    };
+    for (token_id, reason) in [
+        ("", "token_id cannot be empty"),
+        ("card-1234", "longer than 8 bytes"),
+        ("card 1", "' ' is not allowed"),
+        ("card/1", "'/' is not allowed"),
+    ] {
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint(token_id))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidTokenId {
+                reason: reason.to_string()
             }
+        );
+    }
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint("card-A"))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::MintNext {
+                owner: String::from("medusa"),
+                token_uri: None,
+                extension: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(contract.token_count(&deps.storage).unwrap(), 2);
+}
+
+#[test]
+fn transferring_minter_ownership() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let propose_msg = ExecuteMsg::ProposeMinterOwnership {
+        new_minter: String::from("merlin2"),
+        expires: Some(Expiration::AtHeight(mock_env().block.height + 100)),
+    };
+
+    // random cannot propose a new minter
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, propose_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // cannot accept before anything was proposed
+    let new_minter = mock_info("merlin2", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            new_minter.clone(),
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingMinter {});
+
+    // minter can propose
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), propose_msg)
+        .unwrap();
+    let res = contract.pending_minter(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        PendingMinterResponse {
+            pending_minter: Some(String::from("merlin2")),
+            expires: Some(Expiration::AtHeight(mock_env().block.height + 100)),
         }
     );
 
-    // Give random transferring power
-    let approve_msg = ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
+    // proposing does not move minting authority yet
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(Some(MINTER.to_string()), res.minter);
+
+    // only the proposed minter can accept, and only before expiry
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            random,
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let mut late_env = mock_env();
+    late_env.block.height += 100;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            late_env,
+            new_minter.clone(),
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::PendingMinterExpired {});
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            new_minter.clone(),
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap();
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(Some(String::from("merlin2")), res.minter);
+    let res = contract.pending_minter(deps.as_ref()).unwrap();
+    assert_eq!(res.pending_minter, None);
+
+    // old minter lost the right to mint, new one gained it
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: "petrify".to_string(),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), new_minter.clone(), mint_msg)
+        .unwrap();
+
+    // once renounced, nobody can mint anymore
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            new_minter.clone(),
+            ExecuteMsg::RenounceMinterOwnership {},
+        )
+        .unwrap();
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(res.minter, None);
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: "melt".to_string(),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), new_minter, mint_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn granting_roles() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let grant = |role: Role, account: &str, mint_quota: Option<u64>| ExecuteMsg::GrantRole {
+        role,
+        account: account.to_string(),
+        mint_quota,
     };
-    let owner = mock_info("demeter", &[]);
+    let mint = |token_id: &str| {
+        ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("medusa"),
+            token_uri: None,
+            extension: None,
+        })
+    };
+
+    // only the minter or admins can grant roles
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            grant(Role::Admin, "random", None),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let minter = mock_info(MINTER, &[]);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            grant(Role::Minter, "launchpad", Some(2)),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", MINTER)
+            .add_attribute("role", "minter")
+            .add_attribute("account", "launchpad")
+    );
+    for (role, account) in [(Role::Admin, "admin"), (Role::Pauser, "guardian")] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                grant(role, account, None),
+            )
+            .unwrap();
+    }
+    // quotas only apply to minters
+    let admin = mock_info("admin", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            grant(Role::MetadataUpdater, "curator", Some(1)),
+        )
+        .unwrap_err();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            grant(Role::MetadataUpdater, "curator", None),
+        )
+        .unwrap();
+
+    // minters mint up to their quota
+    let launchpad = mock_info("launchpad", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), launchpad.clone(), mint("grow"))
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            launchpad.clone(),
+            ExecuteMsg::BatchMint {
+                mints: vec![
+                    MintMsg::<Extension> {
+                        token_id: "melt".to_string(),
+                        owner: String::from("medusa"),
+                        token_uri: None,
+                        extension: None,
+                    },
+                    MintMsg::<Extension> {
+                        token_id: "sing".to_string(),
+                        owner: String::from("medusa"),
+                        token_uri: None,
+                        extension: None,
+                    },
+                ],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::MintQuotaExceeded { remaining: 1 });
+    contract
+        .execute(deps.as_mut(), mock_env(), launchpad.clone(), mint("melt"))
+        .unwrap();
+    let res = contract
+        .mint_quota(deps.as_ref(), "launchpad".to_string())
+        .unwrap();
+    assert_eq!(
+        res,
+        MintQuotaResponse {
+            limit: Some(2),
+            minted: 2,
+            remaining: Some(0),
+        }
+    );
+    // the minter has no quota, not even one granted by an admin
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            grant(Role::Minter, MINTER, Some(0)),
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint("sing"))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::RevokeRole {
+                role: Role::Minter,
+                account: MINTER.to_string(),
+            },
+        )
+        .unwrap();
+
+    // pausers pause, metadata updaters update metadata
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::SetPauseState {
+                mint: true,
+                transfer: false,
+                approve: false,
+                burn: false,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("curator", &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "grow".to_string(),
+                token_uri: Some("ipfs://grow".to_string()),
+                clear_token_uri: None,
+                extension: None,
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::SetPauseState {
+                mint: false,
+                transfer: false,
+                approve: false,
+                burn: false,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = contract
+        .role_members(deps.as_ref(), Role::Minter, None, None)
+        .unwrap();
+    assert_eq!(res.members, vec!["launchpad"]);
+    let res = contract
+        .roles_of(deps.as_ref(), "admin".to_string())
+        .unwrap();
+    assert_eq!(res.roles, vec![Role::Admin]);
+    let res = contract
+        .roles_of(deps.as_ref(), MINTER.to_string())
+        .unwrap();
+    assert_eq!(res.roles, Role::ALL.to_vec());
+    let res = contract.minter(deps.as_ref()).unwrap();
+    assert_eq!(res.minter, Some(MINTER.to_string()));
+
+    // granting the role again replaces the quota but keeps the count
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            grant(Role::Minter, "launchpad", Some(3)),
+        )
+        .unwrap();
+    let res = contract
+        .mint_quota(deps.as_ref(), "launchpad".to_string())
+        .unwrap();
+    assert_eq!(
+        res,
+        MintQuotaResponse {
+            limit: Some(3),
+            minted: 2,
+            remaining: Some(1),
+        }
+    );
+
+    // revoked minters can no longer mint
+    let revoke = ExecuteMsg::RevokeRole {
+        role: Role::Minter,
+        account: "launchpad".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), revoke.clone())
+        .unwrap();
+    contract
+        .mint_quota(deps.as_ref(), "launchpad".to_string())
+        .unwrap_err();
+
+    // and start counting from zero when granted the role back
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            grant(Role::Minter, "launchpad", Some(3)),
+        )
+        .unwrap();
+    let res = contract
+        .mint_quota(deps.as_ref(), "launchpad".to_string())
+        .unwrap();
+    assert_eq!(
+        res,
+        MintQuotaResponse {
+            limit: Some(3),
+            minted: 0,
+            remaining: Some(3),
+        }
+    );
+    contract
+        .execute(deps.as_mut(), mock_env(), admin, revoke)
+        .unwrap();
+    let res = contract
+        .role_members(deps.as_ref(), Role::Minter, None, None)
+        .unwrap();
+    assert!(res.members.is_empty());
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::SetPauseState {
+                mint: false,
+                transfer: false,
+                approve: false,
+                burn: false,
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), launchpad, mint("swim"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // a role minter taking over minter ownership leaves its quota behind
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            grant(Role::Minter, "studio", Some(0)),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::ProposeMinterOwnership {
+                new_minter: String::from("studio"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let studio = mock_info("studio", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            studio.clone(),
+            ExecuteMsg::AcceptMinterOwnership {},
+        )
+        .unwrap();
+    contract
+        .mint_quota(deps.as_ref(), "studio".to_string())
+        .unwrap_err();
+    contract
+        .execute(deps.as_mut(), mock_env(), studio.clone(), mint("swim"))
+        .unwrap();
+
+    // renouncing the minter role takes every granted role with it
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            studio.clone(),
+            grant(Role::Minter, "launchpad", None),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            studio,
+            ExecuteMsg::RenounceMinterOwnership {},
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("launchpad", &[]),
+            mint("dive"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .mint_quota(deps.as_ref(), "launchpad".to_string())
+        .unwrap_err();
+    let res = contract
+        .roles_of(deps.as_ref(), "admin".to_string())
+        .unwrap();
+    assert!(res.roles.is_empty());
+}
+
+#[test]
+fn updating_collection_info() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let collection_info = CollectionInfo {
+        description: Some(String::from("Spells of all kinds")),
+        image: Some(String::from("ipfs://spells.png")),
+        banner: None,
+        external_link: Some(String::from("https://example.com/spells")),
+        creator: Some(String::from("artist")),
+        royalty_info: Some(CollectionRoyaltyInfo {
+            payment_address: String::from("artist"),
+            share: Decimal::percent(5),
+        }),
+    };
+    let instantiate_msg = |collection_info: CollectionInfo| InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: Some(collection_info),
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+
+    // royalties cannot exceed the sale price
+    let mut too_generous = collection_info.clone();
+    too_generous.royalty_info = Some(CollectionRoyaltyInfo {
+        payment_address: String::from("artist"),
+        share: Decimal::percent(101),
+    });
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(too_generous.clone()),
+        )
+        .unwrap_err();
+
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(collection_info.clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        contract.collection_info(deps.as_ref()).unwrap(),
+        collection_info
+    );
+    // contract info is unchanged
+    let info = contract.contract_info(deps.as_ref()).unwrap();
+    assert_eq!(
+        info,
+        ContractInfoResponse {
+            name: CONTRACT_NAME.to_string(),
+            symbol: SYMBOL.to_string(),
+        }
+    );
+
+    let update =
+        |collection_info: CollectionInfo| ExecuteMsg::UpdateCollectionInfo { collection_info };
+    let mut updated = collection_info.clone();
+    updated.banner = Some(String::from("ipfs://banner.png"));
+
+    // random cannot update
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            update(updated.clone()),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the creator can
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            update(updated.clone()),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "update_collection_info")
+            .add_attribute("sender", "artist")
+    );
+    assert_eq!(contract.collection_info(deps.as_ref()).unwrap(), updated);
+
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("artist", &[]),
+            update(too_generous),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    // and so can the minter, also clearing everything
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            update(CollectionInfo::default()),
+        )
+        .unwrap();
+    assert_eq!(
+        contract.collection_info(deps.as_ref()).unwrap(),
+        CollectionInfo::default()
+    );
+}
+
+#[test]
+fn updating_nft_info() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "petrify".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("medusa"),
+        token_uri: Some("https://www.merriam-webster.com/dictionary/petrfy".to_string()),
+        extension: None,
+    });
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    let update_msg = ExecuteMsg::UpdateNftInfo {
+        token_id: token_id.clone(),
+        token_uri: Some(token_uri.clone()),
+        clear_token_uri: None,
+        extension: None,
+    };
+
+    // neither random nor the owner can update metadata
+    for sender in ["random", "medusa"] {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                update_msg.clone(),
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
+    // minter can fix the typo
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), update_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "update_nft_info")
+            .add_attribute("sender", MINTER)
+            .add_attribute("token_id", token_id.clone())
+    );
+    let info = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    assert_eq!(
+        info,
+        NftInfoResponse::<Extension> {
+            token_uri: Some(token_uri.clone()),
+            extension: None,
+        }
+    );
+
+    // unknown tokens cannot be updated
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "unknown".to_string(),
+                token_uri: None,
+                clear_token_uri: None,
+                extension: Some(Some(Empty {})),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    // only the minter can grant the metadata updater role
+    let grant_msg = ExecuteMsg::GrantRole {
+        role: Role::MetadataUpdater,
+        account: String::from("oracle"),
+        mint_quota: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            grant_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), grant_msg)
+        .unwrap();
+    let res = contract
+        .role_members(deps.as_ref(), Role::MetadataUpdater, None, None)
+        .unwrap();
+    assert_eq!(res.members, vec!["oracle"]);
+
+    // the updater can change the extension, leaving token_uri as is
+    let update_msg = ExecuteMsg::UpdateNftInfo {
+        token_id: token_id.clone(),
+        token_uri: None,
+        clear_token_uri: None,
+        extension: Some(Some(Empty {})),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("oracle", &[]),
+            update_msg.clone(),
+        )
+        .unwrap();
+    let info = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    assert_eq!(
+        info,
+        NftInfoResponse::<Extension> {
+            token_uri: Some(token_uri),
+            extension: Some(Empty {}),
+        }
+    );
+
+    // the token_uri can be removed, but not replaced at the same time
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("oracle", &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: token_id.clone(),
+                token_uri: Some("ipfs://petrify".to_string()),
+                clear_token_uri: Some(true),
+                extension: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("oracle", &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: token_id.clone(),
+                token_uri: None,
+                clear_token_uri: Some(true),
+                extension: None,
+            },
+        )
+        .unwrap();
+    let info = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    assert_eq!(
+        info,
+        NftInfoResponse::<Extension> {
+            token_uri: None,
+            extension: Some(Empty {}),
+        }
+    );
+
+    // once revoked, the updater loses access
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::RevokeRole {
+                role: Role::MetadataUpdater,
+                account: String::from("oracle"),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("oracle", &[]),
+            update_msg,
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn burning() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "petrify".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: MINTER.to_string(),
+        token_uri: Some(token_uri),
+        extension: None,
+    });
+
+    let burn_msg = ExecuteMsg::Burn { token_id };
+
+    // mint some NFT
+    let allowed = mock_info(MINTER, &[]);
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed.clone(), mint_msg)
+        .unwrap();
+
+    // random not allowed to burn
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, burn_msg.clone())
+        .unwrap_err();
+
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let _ = contract
+        .execute(deps.as_mut(), mock_env(), allowed, burn_msg)
+        .unwrap();
+
+    // ensure num tokens decreases
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(0, count.count);
+
+    // trying to get nft returns error
+    let _ = contract
+        .nft_info(deps.as_ref(), "petrify".to_string())
+        .unwrap_err();
+
+    // list the token_ids
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert!(tokens.tokens.is_empty());
+}
+
+#[test]
+fn batch_burning() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for (token_id, owner) in [("grow", "demeter"), ("melt", "demeter"), ("sing", "apollo")] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // cannot burn a batch containing someone else's token
+    let demeter = mock_info("demeter", &[]);
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["sing".to_string(), "grow".to_string()],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), demeter.clone(), burn_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // an approved operator can burn on behalf of the owner
+    let approve_all_msg = ExecuteMsg::ApproveAll {
+        operator: String::from("redeemer"),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), demeter, approve_all_msg)
+        .unwrap();
+    let burn_msg = ExecuteMsg::BatchBurn {
+        token_ids: vec!["grow".to_string(), "melt".to_string()],
+    };
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("redeemer", &[]),
+            burn_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "batch_burn")
+            .add_attribute("sender", "redeemer")
+            .add_attribute("token_id", "grow")
+            .add_attribute("token_id", "melt")
+    );
+
+    let count = contract.num_tokens(deps.as_ref()).unwrap();
+    assert_eq!(1, count.count);
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(vec!["sing"], tokens.tokens);
+}
+
+#[test]
+fn clawing_back_tokens() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: Some(true),
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+    assert!(contract.clawback_enabled(deps.as_ref()).unwrap().enabled);
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in ["grow", "melt"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            ExecuteMsg::Approve {
+                spender: String::from("random"),
+                token_id: "grow".to_string(),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let clawback = |token_id: &str| ExecuteMsg::Clawback {
+        token_id: token_id.to_string(),
+        reason: "fraud".to_string(),
+    };
+
+    // only the minter can claw back tokens, not even their owner
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            clawback("grow"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), clawback("grow"))
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "clawback")
+            .add_attribute("sender", MINTER)
+            .add_attribute("owner", "demeter")
+            .add_attribute("token_id", "grow")
+            .add_attribute("reason", "fraud")
+    );
+    assert!(!contract.tokens.has(&deps.storage, "grow"));
+    assert_eq!(contract.token_count(&deps.storage).unwrap(), 1);
+    let res = contract
+        .balance_of(deps.as_ref(), "demeter".to_string())
+        .unwrap();
+    assert_eq!(res.balance, 1);
+    let res = contract
+        .approved_tokens(
+            deps.as_ref(),
+            mock_env(),
+            "random".to_string(),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(res.tokens.is_empty());
+
+    // disabling is permanent
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::DisableClawback {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::DisableClawback {},
+        )
+        .unwrap();
+    assert!(!contract.clawback_enabled(deps.as_ref()).unwrap().enabled);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, clawback("melt"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::ClawbackDisabled {});
+
+    // contracts without clawback never allow it
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            clawback("melt"),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::ClawbackDisabled {});
+}
+
+#[test]
+fn transferring_nft() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "melt".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: Some(token_uri),
+        extension: None,
+    });
+
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // random cannot transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: token_id.clone(),
+    };
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // owner can
+    let random = mock_info("venus", &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: token_id.clone(),
+    };
+
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap();
+
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "transfer_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "random")
+            .add_attribute("token_id", token_id)
+    );
+}
+
+#[test]
+fn sending_nft() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "melt".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: Some(token_uri),
+        extension: None,
+    });
+
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    let msg = to_binary("You now have the melting power").unwrap();
+    let target = String::from("another_contract");
+    let send_msg = ExecuteMsg::SendNft {
+        contract: target.clone(),
+        token_id: token_id.clone(),
+        msg: msg.clone(),
+    };
+
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // but owner can
+    let random = mock_info("venus", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .unwrap();
+
+    let payload = Cw721ReceiveMsg {
+        sender: String::from("venus"),
+        token_id: token_id.clone(),
+        msg,
+    };
+    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
+    // ensure expected serializes as we think it should
+    match &expected {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, &target)
+        }
+        m => panic!("Unexpected message type: {:?}", m),
+    }
+    // and make sure this is the request sent by the contract
+    assert_eq!(
+        res,
+        Response::new()
+            .add_message(expected)
+            .add_attribute("action", "send_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "another_contract")
+            .add_attribute("token_id", token_id)
+    );
+}
+
+#[test]
+fn tracking_ownership_history() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: Some(true),
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg)
+        .unwrap();
+
+    let token_id = "melt".to_string();
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: None,
+    });
+    let mint_env = mock_env();
+    contract
+        .execute(
+            deps.as_mut(),
+            mint_env.clone(),
+            mock_info(MINTER, &[]),
+            mint_msg,
+        )
+        .unwrap();
+
+    let mut transfer_env = mock_env();
+    transfer_env.block.height += 10;
+    transfer_env.block.time = transfer_env.block.time.plus_seconds(60);
+    contract
+        .execute(
+            deps.as_mut(),
+            transfer_env.clone(),
+            mock_info("venus", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("demeter"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            transfer_env.clone(),
+            mock_info("demeter", &[]),
+            ExecuteMsg::SendNft {
+                contract: String::from("another_contract"),
+                token_id: token_id.clone(),
+                msg: to_binary("melt it").unwrap(),
+            },
+        )
+        .unwrap();
+
+    let history = contract
+        .ownership_history(deps.as_ref(), token_id.clone(), None, None)
+        .unwrap();
+    assert_eq!(
+        history,
+        OwnershipHistoryResponse {
+            history: vec![
+                OwnershipRecord {
+                    index: 0,
+                    owner: String::from("venus"),
+                    action: OwnershipAction::Mint,
+                    height: mint_env.block.height,
+                    time: mint_env.block.time,
+                },
+                OwnershipRecord {
+                    index: 1,
+                    owner: String::from("demeter"),
+                    action: OwnershipAction::Transfer,
+                    height: transfer_env.block.height,
+                    time: transfer_env.block.time,
+                },
+                OwnershipRecord {
+                    index: 2,
+                    owner: String::from("another_contract"),
+                    action: OwnershipAction::Send,
+                    height: transfer_env.block.height,
+                    time: transfer_env.block.time,
+                },
+            ]
+        }
+    );
+
+    // paginate through it
+    let page = contract
+        .ownership_history(deps.as_ref(), token_id.clone(), None, Some(2))
+        .unwrap();
+    assert_eq!(page.history.len(), 2);
+    let page = contract
+        .ownership_history(
+            deps.as_ref(),
+            token_id.clone(),
+            Some(page.history[1].index),
+            None,
+        )
+        .unwrap();
+    assert_eq!(page.history.len(), 1);
+    assert_eq!(page.history[0].owner, "another_contract");
+
+    // burning erases the history, so a token minted again under the same id starts fresh
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("another_contract", &[]),
+            ExecuteMsg::Burn {
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+    let history = contract
+        .ownership_history(deps.as_ref(), token_id.clone(), None, None)
+        .unwrap();
+    assert!(history.history.is_empty());
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+    });
+    contract
+        .execute(
+            deps.as_mut(),
+            mint_env.clone(),
+            mock_info(MINTER, &[]),
+            mint_msg,
+        )
+        .unwrap();
+    let history = contract
+        .ownership_history(deps.as_ref(), token_id, None, None)
+        .unwrap();
+    assert_eq!(
+        history.history,
+        vec![OwnershipRecord {
+            index: 0,
+            owner: String::from("medusa"),
+            action: OwnershipAction::Mint,
+            height: mint_env.block.height,
+            time: mint_env.block.time,
+        }]
+    );
+
+    // nothing is recorded unless enabled
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: "grow".to_string(),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: None,
+    });
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+    let history = contract
+        .ownership_history(deps.as_ref(), "grow".to_string(), None, None)
+        .unwrap();
+    assert!(history.history.is_empty());
+}
+
+#[test]
+fn batch_transferring_and_sending_nfts() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a few tokens
+    let minter = mock_info(MINTER, &[]);
+    for (token_id, owner) in [("grow", "venus"), ("melt", "venus"), ("sing", "apollo")] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // cannot move a batch containing someone else's token
+    let venus = mock_info("venus", &[]);
+    let transfer_msg = ExecuteMsg::BatchTransferNft {
+        recipient: String::from("random"),
+        token_ids: vec!["sing".to_string(), "grow".to_string()],
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), venus.clone(), transfer_msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // owner can move all of her tokens
+    let transfer_msg = ExecuteMsg::BatchTransferNft {
+        recipient: String::from("vault"),
+        token_ids: vec!["grow".to_string(), "melt".to_string()],
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), venus, transfer_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "batch_transfer_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "vault")
+            .add_attribute("token_id", "grow")
+            .add_attribute("token_id", "melt")
+    );
+    let tokens = contract
+        .tokens(deps.as_ref(), String::from("vault"), None, None)
+        .unwrap();
+    assert_eq!(vec!["grow", "melt"], tokens.tokens);
+
+    // the receiving contract is notified once per token
+    let msg = to_binary("Stake these").unwrap();
+    let target = String::from("another_contract");
+    let send_msg = ExecuteMsg::BatchSendNft {
+        contract: target.clone(),
+        token_ids: vec!["grow".to_string(), "melt".to_string()],
+        msg: msg.clone(),
+    };
+    let vault = mock_info("vault", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), vault, send_msg)
+        .unwrap();
+    let expected: Vec<CosmosMsg> = ["grow", "melt"]
+        .iter()
+        .map(|token_id| {
+            Cw721ReceiveMsg {
+                sender: String::from("vault"),
+                token_id: token_id.to_string(),
+                msg: msg.clone(),
+            }
+            .into_cosmos_msg(target.clone())
+            .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_messages(expected)
+            .add_attribute("action", "batch_send_nft")
+            .add_attribute("sender", "vault")
+            .add_attribute("recipient", "another_contract")
+            .add_attribute("token_id", "grow")
+            .add_attribute("token_id", "melt")
+    );
+    let tokens = contract.tokens(deps.as_ref(), target, None, None).unwrap();
+    assert_eq!(vec!["grow", "melt"], tokens.tokens);
+}
+
+#[test]
+fn approving_revoking() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "grow".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
+
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri),
+        extension: None,
+    });
+
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // token owner shows in approval query
+    let res = contract
+        .approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id.clone(),
+            String::from("demeter"),
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovalResponse {
+            approval: Approval {
+                spender: String::from("demeter"),
+                expires: Expiration::Never {}
+            }
+        }
+    );
+
+    // Give random transferring power
+    let approve_msg = ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+    };
+    let owner = mock_info("demeter", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", "demeter")
+            .add_attribute("spender", "random")
+            .add_attribute("token_id", token_id.clone())
+    );
+
+    // test approval query
+    let res = contract
+        .approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id.clone(),
+            String::from("random"),
+            true,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovalResponse {
+            approval: Approval {
+                spender: String::from("random"),
+                expires: Expiration::Never {}
+            }
+        }
+    );
+
+    // random can now transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap();
+
+    // Approvals are removed / cleared
+    let query_msg = QueryMsg::OwnerOf {
+        token_id: token_id.clone(),
+        include_expired: None,
+    };
+    let res: OwnerOfResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
+
+    // Approve, revoke, and check for empty, to test revoke
+    let approve_msg = ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+    };
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+        .unwrap();
+
+    let revoke_msg = ExecuteMsg::Revoke {
+        spender: String::from("random"),
+        token_id,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+        .unwrap();
+
+    // Approvals are now removed / cleared
+    let res: OwnerOfResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
+}
+
+#[test]
+fn querying_approved_tokens() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in ["grow", "melt", "sing"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    let owner = mock_info("demeter", &[]);
+    let approve = |token_id: &str, expires: Option<Expiration>| ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.to_string(),
+        expires,
+    };
+    let expiring = Expiration::AtHeight(mock_env().block.height + 1);
+    for (token_id, expires) in [("grow", None), ("melt", Some(expiring)), ("sing", None)] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                owner.clone(),
+                approve(token_id, expires),
+            )
+            .unwrap();
+    }
+
+    let approved = contract
+        .approved_tokens(
+            deps.as_ref(),
+            mock_env(),
+            String::from("random"),
+            false,
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(
+        approved,
+        ApprovedTokensResponse {
+            tokens: vec![
+                ApprovedToken {
+                    token_id: "grow".to_string(),
+                    expires: Expiration::Never {},
+                },
+                ApprovedToken {
+                    token_id: "melt".to_string(),
+                    expires: expiring,
+                },
+            ]
+        }
+    );
+    let approved = contract
+        .approved_tokens(
+            deps.as_ref(),
+            mock_env(),
+            String::from("random"),
+            false,
+            Some("melt".to_string()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(approved.tokens.len(), 1);
+    assert_eq!(approved.tokens[0].token_id, "sing");
+
+    // expired approvals are hidden unless requested
+    let mut late_env = mock_env();
+    late_env.block.height += 5;
+    let approved = contract
+        .approved_tokens(
+            deps.as_ref(),
+            late_env.clone(),
+            String::from("random"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    let token_ids: Vec<_> = approved.tokens.into_iter().map(|t| t.token_id).collect();
+    assert_eq!(token_ids, vec!["grow", "sing"]);
+    let approved = contract
+        .approved_tokens(
+            deps.as_ref(),
+            late_env,
+            String::from("random"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(approved.tokens.len(), 3);
+
+    // revoking, transferring and burning remove the token from the list
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::Revoke {
+                spender: String::from("random"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: "melt".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::Burn {
+                token_id: "sing".to_string(),
+            },
+        )
+        .unwrap();
+    let approved = contract
+        .approved_tokens(
+            deps.as_ref(),
+            mock_env(),
+            String::from("random"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(approved.tokens.is_empty());
+}
+
+#[test]
+fn bounding_and_pruning_approvals() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let msg = InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit: None,
+        max_limit: None,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: Some(0),
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    let info = mock_info("creator", &[]);
+    contract
+        .instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone())
+        .unwrap_err();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            InstantiateMsg {
+                max_approvals_per_token: Some(2),
+                ..msg
+            },
+        )
+        .unwrap();
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in ["grow", "melt"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    let owner = mock_info("demeter", &[]);
+    let approve =
+        |spender: &str, token_id: &str, expires: Option<Expiration>| ExecuteMsg::Approve {
+            spender: spender.to_string(),
+            token_id: token_id.to_string(),
+            expires,
+        };
+    let expiring = Expiration::AtHeight(mock_env().block.height + 1);
+    for (spender, expires) in [("random", Some(expiring)), ("other", None)] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                owner.clone(),
+                approve(spender, "grow", expires),
+            )
+            .unwrap();
+    }
+
+    // a third approval does not fit
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            approve("third", "grow", None),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::TooManyApprovals { max: 2 });
+    // re-approving an existing spender replaces it
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            approve("other", "grow", None),
+        )
+        .unwrap();
+
+    // once the first approval expired it is dropped to make room
+    let mut later = mock_env();
+    later.block.height += 2;
+    contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            owner.clone(),
+            approve("third", "grow", None),
+        )
+        .unwrap();
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    let spenders: Vec<_> = token.approvals.iter().map(|a| a.spender.as_str()).collect();
+    assert_eq!(spenders, vec!["other", "third"]);
+    let res = contract
+        .approved_tokens(
+            deps.as_ref(),
+            later.clone(),
+            "random".to_string(),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec![]);
+
+    // anyone can prune expired approvals, missing tokens are skipped
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            approve("random", "melt", Some(expiring)),
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::PruneExpiredApprovals {
+                token_ids: vec!["melt".to_string(), "unknown".to_string()],
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "prune_expired_approvals")
+            .add_attribute("sender", "anyone")
+            .add_attribute("pruned", "1")
+    );
+    let token = contract.tokens.load(&deps.storage, "melt").unwrap();
+    assert!(token.approvals.is_empty());
+    let res = contract
+        .approved_tokens(
+            deps.as_ref(),
+            later.clone(),
+            "random".to_string(),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(res.tokens, vec![]);
+
+    // and expired operators
+    for (operator, expires) in [("random", Some(expiring)), ("other", None)] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                owner.clone(),
+                ExecuteMsg::ApproveAll {
+                    operator: operator.to_string(),
+                    expires,
+                },
+            )
+            .unwrap();
+    }
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::PruneExpiredOperators {
+                owner: String::from("demeter"),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "prune_expired_operators")
+            .add_attribute("sender", "anyone")
+            .add_attribute("owner", "demeter")
+            .add_attribute("pruned", "1")
+    );
+    let res = contract
+        .operators(
+            deps.as_ref(),
+            later.clone(),
+            String::from("demeter"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res.operators,
+        vec![Approval {
+            spender: String::from("other"),
+            expires: Expiration::Never {}
+        }]
+    );
+    let res = contract
+        .operator_grants(deps.as_ref(), later, "random".to_string(), true, None, None)
+        .unwrap();
+    assert_eq!(res.grants, vec![]);
+}
+
+#[test]
+fn setting_users() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in ["grow", "melt", "sing"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    let set_user =
+        |token_id: &str, user: Option<&str>, expires: Option<Expiration>| ExecuteMsg::SetUser {
+            token_id: token_id.to_string(),
+            user: user.map(String::from),
+            expires,
+        };
+
+    // only the owner or approved accounts can set a user
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("gamer", &[]),
+            set_user("grow", Some("gamer"), None),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let owner = mock_info("demeter", &[]);
+    let expiring = Expiration::AtHeight(mock_env().block.height + 1);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_user("grow", Some("gamer"), None),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "set_user")
+            .add_attribute("sender", "demeter")
+            .add_attribute("token_id", "grow")
+            .add_attribute("user", "gamer")
+    );
+    for token_id in ["melt", "sing"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                owner.clone(),
+                set_user(token_id, Some("gamer"), Some(expiring)),
+            )
+            .unwrap();
+    }
+    // expired rentals are rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_user("grow", Some("gamer"), Some(Expiration::AtHeight(1))),
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::Expired {});
+
+    let res = contract
+        .user_of(deps.as_ref(), mock_env(), "melt".to_string())
+        .unwrap();
+    assert_eq!(
+        res,
+        UserOfResponse {
+            user: Some(String::from("gamer")),
+            expires: Some(expiring),
+        }
+    );
+    let res = contract
+        .tokens_by_user(deps.as_ref(), mock_env(), "gamer".to_string(), None, None)
+        .unwrap();
+    assert_eq!(res.tokens, vec!["grow", "melt", "sing"]);
+
+    // expired users are hidden
+    let mut later = mock_env();
+    later.block.height += 2;
+    let res = contract
+        .user_of(deps.as_ref(), later.clone(), "melt".to_string())
+        .unwrap();
+    assert_eq!(
+        res,
+        UserOfResponse {
+            user: None,
+            expires: None,
+        }
+    );
+    let res = contract
+        .tokens_by_user(deps.as_ref(), later, "gamer".to_string(), None, None)
+        .unwrap();
+    assert_eq!(res.tokens, vec!["grow"]);
+
+    // the owner can clear the user
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_user("melt", None, None),
+        )
+        .unwrap();
+    let res = contract
+        .tokens_by_user(deps.as_ref(), mock_env(), "gamer".to_string(), None, None)
+        .unwrap();
+    assert_eq!(res.tokens, vec!["grow", "sing"]);
+
+    // transferring clears the user, burning too
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("ceres"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::Burn {
+                token_id: "sing".to_string(),
+            },
+        )
+        .unwrap();
     let res = contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+        .user_of(deps.as_ref(), mock_env(), "grow".to_string())
+        .unwrap();
+    assert_eq!(res.user, None);
+    let res = contract
+        .tokens_by_user(deps.as_ref(), mock_env(), "gamer".to_string(), None, None)
         .unwrap();
+    assert!(res.tokens.is_empty());
+    assert!(!contract.token_users.has(&deps.storage, "sing"));
+}
+
+#[test]
+fn migrating_builds_indexes() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    for (token_id, owner) in [
+        ("grow", "demeter"),
+        ("sing", "demeter"),
+        ("dance", "hermes"),
+    ] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from(owner),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+    for (token_id, owner) in [("grow", "demeter"), ("dance", "hermes")] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                ExecuteMsg::Approve {
+                    spender: String::from("random"),
+                    token_id: token_id.to_string(),
+                    expires: None,
+                },
+            )
+            .unwrap();
+    }
+    for owner in ["demeter", "hermes"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                ExecuteMsg::ApproveAll {
+                    operator: String::from("operator"),
+                    expires: None,
+                },
+            )
+            .unwrap();
+    }
+
+    // simulate a contract deployed before the indexes existed
+    for owner in ["demeter", "hermes"] {
+        let owner = Addr::unchecked(owner);
+        contract.balances.remove(deps.as_mut().storage, &owner);
+        contract.operator_grants.remove(
+            deps.as_mut().storage,
+            (&Addr::unchecked("operator"), &owner),
+        );
+    }
+    for token_id in ["grow", "dance"] {
+        contract.spender_approvals.remove(
+            deps.as_mut().storage,
+            (&Addr::unchecked("random"), token_id),
+        );
+    }
+    cw2::set_contract_version(deps.as_mut().storage, BASE_CONTRACT_NAME, "0.16.0").unwrap();
+
+    // the backfill is spread over several migrations to the same version
+    let mut migrations = 0;
+    loop {
+        let res = contract
+            .migrate(
+                deps.as_mut(),
+                mock_env(),
+                MigrateMsg {
+                    backfill_limit: Some(2),
+                },
+                BASE_CONTRACT_NAME,
+                CONTRACT_VERSION,
+            )
+            .unwrap();
+        migrations += 1;
+        let backfill = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "backfill")
+            .unwrap();
+        if backfill.value == "complete" {
+            break;
+        }
+        assert_eq!(backfill.value, "pending");
+    }
+    assert_eq!(migrations, 5);
     assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "approve")
-            .add_attribute("sender", "demeter")
-            .add_attribute("spender", "random")
-            .add_attribute("token_id", token_id.clone())
+        contract.pending_backfill.may_load(&deps.storage).unwrap(),
+        None
     );
 
-    // test approval query
-    let res = contract
-        .approval(
+    let approved = contract
+        .approved_tokens(
             deps.as_ref(),
             mock_env(),
-            token_id.clone(),
             String::from("random"),
-            true,
+            false,
+            None,
+            None,
         )
         .unwrap();
     assert_eq!(
-        res,
-        ApprovalResponse {
-            approval: Approval {
-                spender: String::from("random"),
-                expires: Expiration::Never {}
+        approved.tokens,
+        vec![
+            ApprovedToken {
+                token_id: "dance".to_string(),
+                expires: Expiration::Never {},
+            },
+            ApprovedToken {
+                token_id: "grow".to_string(),
+                expires: Expiration::Never {},
             }
-        }
+        ]
     );
-
-    // random can now transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = ExecuteMsg::TransferNft {
-        recipient: String::from("person"),
-        token_id: token_id.clone(),
-    };
-    contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("operator"),
+            false,
+            None,
+            None,
+        )
         .unwrap();
-
-    // Approvals are removed / cleared
-    let query_msg = QueryMsg::OwnerOf {
-        token_id: token_id.clone(),
-        include_expired: None,
-    };
-    let res: OwnerOfResponse = from_binary(
-        &contract
-            .query(deps.as_ref(), mock_env(), query_msg.clone())
-            .unwrap(),
-    )
-    .unwrap();
     assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
-        }
+        grants.grants,
+        vec![
+            OperatorGrant {
+                owner: String::from("demeter"),
+                expires: Expiration::Never {},
+            },
+            OperatorGrant {
+                owner: String::from("hermes"),
+                expires: Expiration::Never {},
+            }
+        ]
     );
-
-    // Approve, revoke, and check for empty, to test revoke
-    let approve_msg = ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
-    };
-    let owner = mock_info("person", &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+    let balance = contract
+        .balance_of(deps.as_ref(), String::from("demeter"))
         .unwrap();
-
-    let revoke_msg = ExecuteMsg::Revoke {
-        spender: String::from("random"),
-        token_id,
-    };
-    contract
-        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+    assert_eq!(balance.balance, 2);
+    let balance = contract
+        .balance_of(deps.as_ref(), String::from("hermes"))
         .unwrap();
+    assert_eq!(balance.balance, 1);
 
-    // Approvals are now removed / cleared
-    let res: OwnerOfResponse = from_binary(
-        &contract
-            .query(deps.as_ref(), mock_env(), query_msg)
-            .unwrap(),
-    )
-    .unwrap();
-    assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
-        }
-    );
+    // once complete, migrating again leaves storage alone
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::default(),
+            BASE_CONTRACT_NAME,
+            CONTRACT_VERSION,
+        )
+        .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "backfill"));
 }
 
 #[test]
@@ -636,41 +3351,485 @@ fn approving_all_revoking_all() {
         .execute(deps.as_mut(), mock_env(), owner, revoke_all_msg)
         .unwrap();
 
-    // Approvals are removed / cleared without affecting others
-    let res = contract
-        .operators(
+    // Approvals are removed / cleared without affecting others
+    let res = contract
+        .operators(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorsResponse {
+            operators: vec![cw721::Approval {
+                spender: String::from("buddy"),
+                expires: buddy_expires,
+            }]
+        }
+    );
+
+    // ensure the filter works (nothing should be here
+    let mut late_env = mock_env();
+    late_env.block.height = 1234568; //expired
+    let res = contract
+        .operators(
+            deps.as_ref(),
+            late_env,
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(0, res.operators.len());
+}
+
+#[test]
+fn approving_all_scoped() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER, &[]);
+    for token_id in ["grow", "melt", "sing"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // transfer-only rights on two tokens
+    let owner = mock_info("demeter", &[]);
+    let scope = OperatorScope {
+        can_approve: false,
+        token_ids: Some(vec!["grow".to_string(), "melt".to_string()]),
+    };
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAllScoped {
+                operator: String::from("staking"),
+                expires: None,
+                scope: scope.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "approve_all_scoped")
+            .add_attribute("sender", "demeter")
+            .add_attribute("operator", "staking")
+            .add_attribute("can_approve", "false")
+    );
+    let is_operator = contract
+        .is_operator(
+            deps.as_ref(),
+            mock_env(),
+            String::from("demeter"),
+            String::from("staking"),
+        )
+        .unwrap();
+    assert_eq!(
+        is_operator,
+        IsOperatorResponse {
+            expires: Some(Expiration::Never {}),
+            scope: Some(scope.clone()),
+        }
+    );
+
+    // listing operators shows the scope next to unscoped grants
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("buddy"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let query_msg = QueryMsg::AllOperators {
+        owner: String::from("demeter"),
+        include_expired: None,
+        start_after: None,
+        start_before: None,
+        order: None,
+        limit: None,
+    };
+    let res: OperatorsWithScopeResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.operators,
+        vec![
+            OperatorWithScope {
+                spender: String::from("buddy"),
+                expires: Expiration::Never {},
+                scope: None,
+            },
+            OperatorWithScope {
+                spender: String::from("staking"),
+                expires: Expiration::Never {},
+                scope: Some(scope),
+            }
+        ]
+    );
+    // unscoped grants still read as plain approvals
+    let unscoped = OperatorsWithScopeResponse {
+        operators: res.operators[..1].to_vec(),
+    };
+    let plain: OperatorsResponse = from_binary(&to_binary(&unscoped).unwrap()).unwrap();
+    assert_eq!(
+        plain.operators,
+        vec![Approval {
+            spender: String::from("buddy"),
+            expires: Expiration::Never {},
+        }]
+    );
+
+    // without a token id, operators limited to some tokens are turned away
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    let staking = mock_info("staking", &[]);
+    let err = contract
+        .check_can_send(deps.as_ref(), &mock_env(), &staking, &token)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    contract
+        .check_can_send_token(deps.as_ref(), &mock_env(), &staking, "grow", &token)
+        .unwrap();
+    contract
+        .check_can_approve(deps.as_ref(), &mock_env(), &mock_info("buddy", &[]), &token)
+        .unwrap();
+
+    let approve = |token_id: &str| ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.to_string(),
+        expires: None,
+    };
+    let transfer = |token_id: &str| ExecuteMsg::TransferNft {
+        recipient: String::from("staking"),
+        token_id: token_id.to_string(),
+    };
+
+    // cannot approve others
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), staking.clone(), approve("grow"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    // cannot touch tokens out of scope
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), staking.clone(), transfer("sing"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    // but can move tokens in scope
+    contract
+        .execute(deps.as_mut(), mock_env(), staking.clone(), transfer("grow"))
+        .unwrap();
+
+    // a plain ApproveAll lifts the scope
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: String::from("staking"),
+                expires: None,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), staking.clone(), approve("sing"))
+        .unwrap();
+
+    // and revoking removes both
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            ExecuteMsg::ApproveAllScoped {
+                operator: String::from("staking"),
+                expires: None,
+                scope: OperatorScope {
+                    can_approve: true,
+                    token_ids: None,
+                },
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            ExecuteMsg::RevokeAll {
+                operator: String::from("staking"),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), staking, transfer("melt"))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert!(!contract.operator_scopes.has(
+        deps.as_ref().storage,
+        (&Addr::unchecked("demeter"), &Addr::unchecked("staking"))
+    ));
+}
+
+#[test]
+fn querying_operator_grants() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let expiring = Expiration::AtHeight(mock_env().block.height + 1);
+    for (owner, expires) in [
+        ("demeter", None),
+        ("medusa", Some(expiring)),
+        ("venus", None),
+    ] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(owner, &[]),
+                ExecuteMsg::ApproveAll {
+                    operator: String::from("custody"),
+                    expires,
+                },
+            )
+            .unwrap();
+    }
+
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("custody"),
+            false,
+            None,
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(
+        grants,
+        OperatorGrantsResponse {
+            grants: vec![
+                OperatorGrant {
+                    owner: String::from("demeter"),
+                    expires: Expiration::Never {},
+                },
+                OperatorGrant {
+                    owner: String::from("medusa"),
+                    expires: expiring,
+                },
+            ]
+        }
+    );
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            mock_env(),
+            String::from("custody"),
+            false,
+            Some(String::from("medusa")),
+            None,
+        )
+        .unwrap();
+    assert_eq!(grants.grants.len(), 1);
+    assert_eq!(grants.grants[0].owner, "venus");
+
+    let is_operator = contract
+        .is_operator(
             deps.as_ref(),
             mock_env(),
-            String::from("person"),
+            String::from("medusa"),
+            String::from("custody"),
+        )
+        .unwrap();
+    assert_eq!(is_operator.expires, Some(expiring));
+
+    // expired grants are hidden unless requested
+    let mut late_env = mock_env();
+    late_env.block.height += 5;
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            late_env.clone(),
+            String::from("custody"),
             false,
             None,
             None,
         )
         .unwrap();
+    let owners: Vec<_> = grants.grants.into_iter().map(|g| g.owner).collect();
+    assert_eq!(owners, vec!["demeter", "venus"]);
+    let grants = contract
+        .operator_grants(
+            deps.as_ref(),
+            late_env.clone(),
+            String::from("custody"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(grants.grants.len(), 3);
+    let is_operator = contract
+        .is_operator(
+            deps.as_ref(),
+            late_env,
+            String::from("medusa"),
+            String::from("custody"),
+        )
+        .unwrap();
     assert_eq!(
-        res,
-        OperatorsResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("buddy"),
-                expires: buddy_expires,
-            }]
+        is_operator,
+        IsOperatorResponse {
+            expires: None,
+            scope: None,
         }
     );
 
-    // ensure the filter works (nothing should be here
-    let mut late_env = mock_env();
-    late_env.block.height = 1234568; //expired
-    let res = contract
-        .operators(
+    // revoking removes the grant
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            ExecuteMsg::RevokeAll {
+                operator: String::from("custody"),
+            },
+        )
+        .unwrap();
+    let grants = contract
+        .operator_grants(
             deps.as_ref(),
-            late_env,
-            String::from("person"),
-            false,
+            mock_env(),
+            String::from("custody"),
+            true,
             None,
             None,
         )
         .unwrap();
-    assert_eq!(0, res.operators.len());
+    let owners: Vec<_> = grants.grants.into_iter().map(|g| g.owner).collect();
+    assert_eq!(owners, vec!["medusa", "venus"]);
+    let is_operator = contract
+        .is_operator(
+            deps.as_ref(),
+            mock_env(),
+            String::from("demeter"),
+            String::from("custody"),
+        )
+        .unwrap();
+    assert_eq!(is_operator.expires, None);
+}
+
+#[test]
+fn counting_balances() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let balance_of = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, owner: &str| {
+        contract
+            .balance_of(deps.as_ref(), owner.to_string())
+            .unwrap()
+            .balance
+    };
+
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::BatchMint {
+                mints: ["grow", "melt", "sing"]
+                    .into_iter()
+                    .map(|token_id| MintMsg::<Extension> {
+                        token_id: token_id.to_string(),
+                        owner: String::from("demeter"),
+                        token_uri: None,
+                        extension: None,
+                    })
+                    .collect(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: "swim".to_string(),
+                owner: String::from("ceres"),
+                token_uri: None,
+                extension: None,
+            }),
+        )
+        .unwrap();
+    assert_eq!(balance_of(&deps, "demeter"), 3);
+    assert_eq!(balance_of(&deps, "ceres"), 1);
+    assert_eq!(balance_of(&deps, "venus"), 0);
+
+    let demeter = mock_info("demeter", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            demeter.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("ceres"),
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    // sending to yourself changes nothing
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            demeter.clone(),
+            ExecuteMsg::TransferNft {
+                recipient: String::from("demeter"),
+                token_id: "melt".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(balance_of(&deps, "demeter"), 2);
+    assert_eq!(balance_of(&deps, "ceres"), 2);
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            demeter,
+            ExecuteMsg::BatchBurn {
+                token_ids: vec!["melt".to_string(), "sing".to_string()],
+            },
+        )
+        .unwrap();
+    assert_eq!(balance_of(&deps, "demeter"), 0);
+    assert_eq!(balance_of(&deps, "ceres"), 2);
+    assert!(!contract
+        .balances
+        .has(deps.as_ref().storage, &Addr::unchecked("demeter")));
 }
 
 #[test]
@@ -749,3 +3908,419 @@ fn query_tokens_by_owner() {
         .unwrap();
     assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
 }
+
+#[test]
+fn querying_tokens_in_order() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let demeter = String::from("demeter");
+    for (token_id, owner) in [
+        ("grow1", "demeter"),
+        ("grow2", "ceres"),
+        ("sing", "demeter"),
+    ] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from(owner),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    let expected = vec!["grow1".to_string(), "grow2".to_string(), "sing".to_string()];
+    let by_demeter = vec!["grow1".to_string(), "sing".to_string()];
+
+    // newest ids first, and page back through them
+    let tokens = contract
+        .all_tokens_in_range(deps.as_ref(), None, None, SortOrder::Descending, Some(2))
+        .unwrap();
+    assert_eq!(
+        vec![expected[2].clone(), expected[1].clone()],
+        tokens.tokens
+    );
+    let tokens = contract
+        .all_tokens_in_range(
+            deps.as_ref(),
+            None,
+            Some(expected[1].clone()),
+            SortOrder::Descending,
+            None,
+        )
+        .unwrap();
+    assert_eq!(&expected[..1], &tokens.tokens[..]);
+    // both bounds are exclusive
+    let tokens = contract
+        .all_tokens_in_range(
+            deps.as_ref(),
+            Some(expected[0].clone()),
+            Some(expected[2].clone()),
+            SortOrder::Ascending,
+            None,
+        )
+        .unwrap();
+    assert_eq!(&expected[1..2], &tokens.tokens[..]);
+
+    // the same for the tokens of one owner
+    let tokens = contract
+        .tokens_in_range(
+            deps.as_ref(),
+            demeter.clone(),
+            None,
+            None,
+            SortOrder::Descending,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        vec![by_demeter[1].clone(), by_demeter[0].clone()],
+        tokens.tokens
+    );
+    let tokens = contract
+        .tokens_in_range(
+            deps.as_ref(),
+            demeter.clone(),
+            None,
+            Some(by_demeter[1].clone()),
+            SortOrder::Descending,
+            None,
+        )
+        .unwrap();
+    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
+
+    // and for the operators of one owner
+    for operator in ["buddy", "operator"] {
+        let approve_all_msg = ExecuteMsg::ApproveAll {
+            operator: String::from(operator),
+            expires: None,
+        };
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("demeter", &[]),
+                approve_all_msg,
+            )
+            .unwrap();
+    }
+    let res = contract
+        .operators_in_range(
+            deps.as_ref(),
+            mock_env(),
+            demeter.clone(),
+            true,
+            None,
+            None,
+            SortOrder::Descending,
+            Some(1),
+        )
+        .unwrap();
+    assert_eq!(res.operators.len(), 1);
+    assert_eq!(res.operators[0].spender, "operator");
+    let res = contract
+        .operators_in_range(
+            deps.as_ref(),
+            mock_env(),
+            demeter.clone(),
+            true,
+            None,
+            Some(String::from("operator")),
+            SortOrder::Descending,
+            None,
+        )
+        .unwrap();
+    assert_eq!(res.operators.len(), 1);
+    assert_eq!(res.operators[0].spender, "buddy");
+
+    // the query messages default to ascending
+    let query_msg = QueryMsg::AllTokens {
+        start_after: None,
+        start_before: Some(expected[2].clone()),
+        order: None,
+        limit: None,
+    };
+    let tokens: TokensResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(&expected[..2], &tokens.tokens[..]);
+    let query_msg = QueryMsg::Tokens {
+        owner: demeter.clone(),
+        start_after: None,
+        start_before: None,
+        order: Some(SortOrder::Descending),
+        limit: Some(1),
+    };
+    let tokens: TokensResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+
+    // the helpers keep the old ascending order
+    let mut querier = MockQuerier::<Empty>::new(&[]);
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let res = contract.query(deps.as_ref(), mock_env(), from_binary(msg).unwrap());
+            SystemResult::Ok(res.into())
+        }
+        _ => panic!("unexpected query"),
+    });
+    let querier = QuerierWrapper::<Empty>::new(&querier);
+    let cw721 =
+        helpers::Cw721Contract::<Empty, Empty>(Addr::unchecked("cw721"), PhantomData, PhantomData);
+    let tokens = cw721.all_tokens(&querier, None, None).unwrap();
+    assert_eq!(expected, tokens.tokens);
+    let tokens = cw721.tokens(&querier, demeter, None, None).unwrap();
+    assert_eq!(by_demeter, tokens.tokens);
+}
+
+#[test]
+fn querying_tokens_with_info() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let demeter = String::from("demeter");
+    for (token_id, owner) in [
+        ("grow1", "demeter"),
+        ("grow2", "ceres"),
+        ("sing", "demeter"),
+    ] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from(owner),
+            token_uri: Some(format!("https://example.com/{}", token_id)),
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // one approval expires, the other one stays
+    let env = mock_env();
+    for (spender, expires) in [
+        ("random", Expiration::AtHeight(env.block.height + 1)),
+        ("buddy", Expiration::Never {}),
+    ] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("demeter", &[]),
+                ExecuteMsg::Approve {
+                    spender: String::from(spender),
+                    token_id: "grow1".to_string(),
+                    expires: Some(expires),
+                },
+            )
+            .unwrap();
+    }
+    let mut later = mock_env();
+    later.block.height += 1;
+
+    let grow1 = TokenWithInfo {
+        token_id: "grow1".to_string(),
+        owner: demeter.clone(),
+        approvals: vec![cw721::Approval {
+            spender: String::from("buddy"),
+            expires: Expiration::Never {},
+        }],
+        token_uri: Some("https://example.com/grow1".to_string()),
+        extension: None,
+    };
+    let grow2 = TokenWithInfo {
+        token_id: "grow2".to_string(),
+        owner: String::from("ceres"),
+        approvals: vec![],
+        token_uri: Some("https://example.com/grow2".to_string()),
+        extension: None,
+    };
+    let sing = TokenWithInfo {
+        token_id: "sing".to_string(),
+        owner: demeter.clone(),
+        approvals: vec![],
+        token_uri: Some("https://example.com/sing".to_string()),
+        extension: None,
+    };
+
+    // expired approvals are left out
+    let tokens = contract
+        .tokens_with_info(deps.as_ref(), later.clone(), demeter.clone(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![grow1.clone(), sing.clone()]);
+
+    // paginate
+    let tokens = contract
+        .tokens_with_info(deps.as_ref(), later.clone(), demeter.clone(), None, Some(1))
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![grow1.clone()]);
+    let tokens = contract
+        .tokens_with_info(
+            deps.as_ref(),
+            later.clone(),
+            demeter.clone(),
+            Some("grow1".to_string()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![sing.clone()]);
+    let tokens = contract
+        .all_tokens_with_info(deps.as_ref(), later.clone(), None, Some(2))
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![grow1.clone(), grow2.clone()]);
+    let tokens = contract
+        .all_tokens_with_info(
+            deps.as_ref(),
+            later.clone(),
+            Some("grow2".to_string()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![sing.clone()]);
+
+    // through the query message
+    let query_msg = QueryMsg::TokensWithInfo {
+        owner: demeter.clone(),
+        start_after: None,
+        limit: None,
+    };
+    let tokens: TokensWithInfoResponse<Extension> = from_binary(
+        &contract
+            .query(deps.as_ref(), later.clone(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(tokens.tokens, vec![grow1.clone(), sing.clone()]);
+    let query_msg = QueryMsg::AllTokensWithInfo {
+        start_after: Some("grow1".to_string()),
+        limit: None,
+    };
+    let tokens: TokensWithInfoResponse<Extension> = from_binary(
+        &contract
+            .query(deps.as_ref(), later.clone(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(tokens.tokens, vec![grow2.clone(), sing.clone()]);
+
+    // and through the helpers of another contract
+    let collection_info = contract.collection_info(deps.as_ref()).unwrap();
+    let mut querier = MockQuerier::<Empty>::new(&[]);
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let res = contract.query(deps.as_ref(), later.clone(), from_binary(msg).unwrap());
+            SystemResult::Ok(res.into())
+        }
+        _ => panic!("unexpected query"),
+    });
+    let querier = QuerierWrapper::<Empty>::new(&querier);
+    let cw721 =
+        helpers::Cw721Contract::<Empty, Empty>(Addr::unchecked("cw721"), PhantomData, PhantomData);
+    let tokens = cw721
+        .tokens_with_info::<_, Extension>(&querier, demeter, Some("grow1".to_string()), None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![sing.clone()]);
+    let tokens = cw721
+        .all_tokens_with_info::<Extension>(&querier, None, Some(2))
+        .unwrap();
+    assert_eq!(tokens.tokens, vec![grow1, grow2]);
+    assert_eq!(cw721.collection_info(&querier).unwrap(), collection_info);
+}
+
+#[test]
+fn configuring_query_limits() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+    let instantiate_msg = |default_limit: Option<u32>, max_limit: Option<u32>| InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: String::from(MINTER),
+        max_supply: None,
+        track_ownership_history: None,
+        default_limit,
+        max_limit,
+        token_id_sequence: None,
+        collection_info: None,
+        max_approvals_per_token: None,
+        token_id_rules: None,
+        enable_clawback: None,
+    };
+    let info = mock_info("creator", &[]);
+
+    // limits must be usable
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg(Some(0), None),
+        )
+        .unwrap_err();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg(Some(5), Some(3)),
+        )
+        .unwrap_err();
+
+    // defaults apply if unset
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            instantiate_msg(None, None),
+        )
+        .unwrap();
+    let limits = contract.query_limits_info(deps.as_ref()).unwrap();
+    assert_eq!(
+        limits,
+        QueryLimitsResponse {
+            default_limit: 10,
+            max_limit: 100,
+        }
+    );
+
+    let mut deps = mock_dependencies();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            instantiate_msg(Some(2), Some(3)),
+        )
+        .unwrap();
+    for token_id in ["grow", "melt", "sing", "swim", "walk"] {
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), mint_msg)
+            .unwrap();
+    }
+
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(tokens.tokens.len(), 2);
+    let tokens = contract
+        .tokens(deps.as_ref(), String::from("demeter"), None, Some(50))
+        .unwrap();
+    assert_eq!(tokens.tokens.len(), 3);
+    let tokens = contract
+        .all_tokens_with_info(deps.as_ref(), mock_env(), None, Some(50))
+        .unwrap();
+    assert_eq!(tokens.tokens.len(), 3);
+}
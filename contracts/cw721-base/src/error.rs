@@ -17,4 +17,49 @@ pub enum ContractError {
 
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
+
+    #[error("Maximum token supply reached")]
+    MaxSupplyReached {},
+
+    #[error("Minting has been permanently frozen")]
+    MintingFrozen {},
+
+    #[error("Clawback is disabled")]
+    ClawbackDisabled {},
+
+    #[error("Mint quota exceeded, {remaining} tokens left")]
+    MintQuotaExceeded { remaining: u64 },
+
+    #[error("Paused: {action}")]
+    Paused { action: String },
+
+    #[error("Invalid token_id: {reason}")]
+    InvalidTokenId { reason: String },
+
+    #[error("A token can have at most {max} approvals")]
+    TooManyApprovals { max: u32 },
+
+    #[error("No pending minter ownership transfer")]
+    NoPendingMinter {},
+
+    #[error("Pending minter ownership transfer has expired")]
+    PendingMinterExpired {},
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    WrongContract { previous_contract: String },
+
+    #[error("Cannot migrate from newer version ({previous_version}) to older ({new_version})")]
+    CannotDowngrade {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
@@ -1,18 +1,28 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{Binary, CustomMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Api, Binary, BlockInfo, CustomMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Storage,
+};
 
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw721::{ContractInfoResponse, Cw721Execute, Cw721ReceiveMsg, Expiration};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg};
-use crate::state::{Approval, Cw721Contract, TokenInfo};
+use crate::msg::{
+    CollectionInfo, ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, MintNextResponse,
+    OperatorScope, OwnershipAction, Role, TokenIdRules,
+};
+use crate::state::{
+    Approval, Cw721Contract, MintQuota, OwnershipEntry, PauseState, PendingMinter, QueryLimits,
+    TokenIdCounter, TokenInfo, TokenUser,
+};
+use crate::upgrades;
 
 // Version info for migration
-const CONTRACT_NAME: &str = "crates.io:cw721-base";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const CONTRACT_NAME: &str = "crates.io:cw721-base";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 impl<'a, T, C, E, Q> Cw721Contract<'a, T, C, E, Q>
 where
@@ -37,6 +47,65 @@ where
         self.contract_info.save(deps.storage, &info)?;
         let minter = deps.api.addr_validate(&msg.minter)?;
         self.minter.save(deps.storage, &minter)?;
+        if let Some(max_supply) = msg.max_supply {
+            if max_supply == 0 {
+                return Err(StdError::generic_err(
+                    "max_supply must be greater than zero",
+                ));
+            }
+            self.max_supply.save(deps.storage, &max_supply)?;
+        }
+        if msg.enable_clawback.unwrap_or_default() {
+            self.clawback_enabled.save(deps.storage, &true)?;
+        }
+        if msg.track_ownership_history.unwrap_or_default() {
+            self.track_ownership_history.save(deps.storage, &true)?;
+        }
+        if let Some(collection_info) = msg.collection_info {
+            let collection_info = validate_collection_info(deps.api, collection_info)?;
+            self.collection_info.save(deps.storage, &collection_info)?;
+        }
+        if let Some(max_approvals) = msg.max_approvals_per_token {
+            if max_approvals == 0 {
+                return Err(StdError::generic_err(
+                    "max_approvals_per_token must be greater than zero",
+                ));
+            }
+            self.max_approvals.save(deps.storage, &max_approvals)?;
+        }
+        if let Some(sequence) = msg.token_id_sequence {
+            let counter = TokenIdCounter {
+                next: sequence.start,
+                prefix: sequence.prefix.unwrap_or_default(),
+            };
+            self.token_id_sequence.save(deps.storage, &counter)?;
+        }
+        if let Some(rules) = msg.token_id_rules {
+            if rules.max_length == Some(0) {
+                return Err(StdError::generic_err(
+                    "max_length must be greater than zero",
+                ));
+            }
+            // MintNext would fail on every call otherwise
+            let next_token_id = self.next_token_id(deps.storage)?.token_id();
+            validate_token_id(&rules, &next_token_id).map_err(|err| {
+                StdError::generic_err(format!("token_id_sequence breaks token_id_rules: {}", err))
+            })?;
+            self.token_id_rules.save(deps.storage, &rules)?;
+        }
+        if msg.default_limit.is_some() || msg.max_limit.is_some() {
+            let defaults = QueryLimits::default();
+            let limits = QueryLimits {
+                default_limit: msg.default_limit.unwrap_or(defaults.default_limit),
+                max_limit: msg.max_limit.unwrap_or(defaults.max_limit),
+            };
+            if limits.default_limit == 0 || limits.default_limit > limits.max_limit {
+                return Err(StdError::generic_err(
+                    "default_limit must be greater than zero and at most max_limit",
+                ));
+            }
+            self.query_limits.save(deps.storage, &limits)?;
+        }
         Ok(Response::default())
     }
 
@@ -47,8 +116,17 @@ where
         info: MessageInfo,
         msg: ExecuteMsg<T, E>,
     ) -> Result<Response<C>, ContractError> {
+        self.check_not_paused(deps.as_ref(), &msg)?;
+
         match msg {
             ExecuteMsg::Mint(msg) => self.mint(deps, env, info, msg),
+            ExecuteMsg::BatchMint { mints } => self.batch_mint(deps, env, info, mints),
+            ExecuteMsg::MintNext {
+                owner,
+                token_uri,
+                extension,
+            } => self.mint_next(deps, env, info, owner, token_uri, extension),
+            ExecuteMsg::FreezeMinting {} => self.freeze_minting(deps, env, info),
             ExecuteMsg::Approve {
                 spender,
                 token_id,
@@ -60,7 +138,23 @@ where
             ExecuteMsg::ApproveAll { operator, expires } => {
                 self.approve_all(deps, env, info, operator, expires)
             }
+            ExecuteMsg::ApproveAllScoped {
+                operator,
+                expires,
+                scope,
+            } => self.approve_all_scoped(deps, env, info, operator, expires, scope),
             ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
+            ExecuteMsg::SetUser {
+                token_id,
+                user,
+                expires,
+            } => self.set_user(deps, env, info, token_id, user, expires),
+            ExecuteMsg::PruneExpiredApprovals { token_ids } => {
+                self.prune_expired_approvals(deps, env, info, token_ids)
+            }
+            ExecuteMsg::PruneExpiredOperators { owner } => {
+                self.prune_expired_operators(deps, env, info, owner)
+            }
             ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
@@ -70,10 +164,113 @@ where
                 token_id,
                 msg,
             } => self.send_nft(deps, env, info, contract, token_id, msg),
+            ExecuteMsg::BatchTransferNft {
+                recipient,
+                token_ids,
+            } => self.batch_transfer_nft(deps, env, info, recipient, token_ids),
+            ExecuteMsg::BatchSendNft {
+                contract,
+                token_ids,
+                msg,
+            } => self.batch_send_nft(deps, env, info, contract, token_ids, msg),
             ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
+            ExecuteMsg::BatchBurn { token_ids } => self.batch_burn(deps, env, info, token_ids),
+            ExecuteMsg::Clawback { token_id, reason } => {
+                self.clawback(deps, env, info, token_id, reason)
+            }
+            ExecuteMsg::DisableClawback {} => self.disable_clawback(deps, env, info),
+            ExecuteMsg::SetPauseState {
+                mint,
+                transfer,
+                approve,
+                burn,
+            } => {
+                let pause_state = PauseState {
+                    mint,
+                    transfer,
+                    approve,
+                    burn,
+                };
+                self.set_pause_state(deps, env, info, pause_state)
+            }
+            ExecuteMsg::UpdateNftInfo {
+                token_id,
+                token_uri,
+                clear_token_uri,
+                extension,
+            } => self.update_nft_info(
+                deps,
+                env,
+                info,
+                token_id,
+                token_uri,
+                clear_token_uri.unwrap_or_default(),
+                extension,
+            ),
+            ExecuteMsg::UpdateCollectionInfo { collection_info } => {
+                self.update_collection_info(deps, env, info, collection_info)
+            }
+            ExecuteMsg::GrantRole {
+                role,
+                account,
+                mint_quota,
+            } => self.grant_role(deps, env, info, role, account, mint_quota),
+            ExecuteMsg::RevokeRole { role, account } => {
+                self.revoke_role(deps, env, info, role, account)
+            }
+            ExecuteMsg::ProposeMinterOwnership {
+                new_minter,
+                expires,
+            } => self.propose_minter_ownership(deps, env, info, new_minter, expires),
+            ExecuteMsg::AcceptMinterOwnership {} => self.accept_minter_ownership(deps, env, info),
+            ExecuteMsg::RenounceMinterOwnership {} => {
+                self.renounce_minter_ownership(deps, env, info)
+            }
             ExecuteMsg::Extension { msg: _ } => Ok(Response::default()),
         }
     }
+
+    /// Migrates a contract previously stored as `contract_name` to `contract_version`.
+    /// Contracts extending this one pass their own name and version, so they can
+    /// only be migrated from themselves and never to an older release.
+    /// A large storage backfill may take several migrations to the same version.
+    pub fn migrate(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: MigrateMsg,
+        contract_name: &str,
+        contract_version: &str,
+    ) -> Result<Response<C>, ContractError> {
+        let stored = get_contract_version(deps.storage)?;
+        if stored.contract != contract_name {
+            return Err(ContractError::WrongContract {
+                previous_contract: stored.contract,
+            });
+        }
+
+        let previous_version: semver::Version = stored.version.parse()?;
+        let new_version: semver::Version = contract_version.parse()?;
+        if previous_version > new_version {
+            return Err(ContractError::CannotDowngrade {
+                previous_version: stored.version,
+                new_version: contract_version.to_string(),
+            });
+        }
+
+        upgrades::migrate(self, deps.storage, &previous_version)?;
+        set_contract_version(deps.storage, contract_name, contract_version)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", stored.version)
+            .add_attribute("to_version", contract_version);
+        if let Some(complete) = upgrades::backfill(self, deps.storage, msg.backfill_limit)? {
+            let status = if complete { "complete" } else { "pending" };
+            res = res.add_attribute("backfill", status);
+        }
+        Ok(res)
+    }
 }
 
 // TODO pull this into some sort of trait extension??
@@ -86,37 +283,556 @@ where
 {
     pub fn mint(
         &self,
-        deps: DepsMut,
-        _env: Env,
+        mut deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         msg: MintMsg<T>,
     ) -> Result<Response<C>, ContractError> {
-        let minter = self.minter.load(deps.storage)?;
+        self.check_can_mint(deps.as_ref(), &info, 1)?;
 
-        if info.sender != minter {
-            return Err(ContractError::Unauthorized {});
-        }
+        let owner = msg.owner.clone();
+        let token_id = msg.token_id.clone();
+        self._mint(deps.branch(), &env, msg)?;
+        self.increment_tokens(deps.storage)?;
+        self._count_mints(deps.storage, &info.sender, 1)?;
 
-        // create the token
-        let token = TokenInfo {
-            owner: deps.api.addr_validate(&msg.owner)?,
-            approvals: vec![],
-            token_uri: msg.token_uri,
-            extension: msg.extension,
-        };
-        self.tokens
-            .update(deps.storage, &msg.token_id, |old| match old {
-                Some(_) => Err(ContractError::Claimed {}),
-                None => Ok(token),
-            })?;
+        Ok(Response::new()
+            .add_attribute("action", "mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn mint_next(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+        token_uri: Option<String>,
+        extension: T,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_mint(deps.as_ref(), &info, 1)?;
 
+        let mut counter = self.next_token_id(deps.storage)?;
+        let token_id = counter.token_id();
+        let msg = MintMsg {
+            token_id: token_id.clone(),
+            owner: owner.clone(),
+            token_uri,
+            extension,
+        };
+        self._mint(deps.branch(), &env, msg)?;
         self.increment_tokens(deps.storage)?;
+        self._count_mints(deps.storage, &info.sender, 1)?;
+        counter.next += 1;
+        self.token_id_sequence.save(deps.storage, &counter)?;
 
         Ok(Response::new()
+            .set_data(to_binary(&MintNextResponse {
+                token_id: token_id.clone(),
+            })?)
             .add_attribute("action", "mint")
             .add_attribute("minter", info.sender)
-            .add_attribute("owner", msg.owner)
-            .add_attribute("token_id", msg.token_id))
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn batch_mint(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        mints: Vec<MintMsg<T>>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_mint(deps.as_ref(), &info, mints.len() as u64)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", &info.sender);
+        let amount = mints.len() as u64;
+        for msg in mints {
+            res = res
+                .add_attribute("owner", msg.owner.clone())
+                .add_attribute("token_id", msg.token_id.clone());
+            self._mint(deps.branch(), &env, msg)?;
+        }
+        self.increase_tokens(deps.storage, amount)?;
+        self._count_mints(deps.storage, &info.sender, amount)?;
+
+        Ok(res)
+    }
+
+    pub fn approve_all_scoped(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        expires: Option<Expiration>,
+        scope: OperatorScope,
+    ) -> Result<Response<C>, ContractError> {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        self.operators
+            .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+        self.operator_grants
+            .save(deps.storage, (&operator_addr, &info.sender), &expires)?;
+        self.operator_scopes
+            .save(deps.storage, (&info.sender, &operator_addr), &scope)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_all_scoped")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator)
+            .add_attribute("can_approve", scope.can_approve.to_string()))
+    }
+
+    pub fn set_user(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        user: Option<String>,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        // ensure we have permissions
+        self.check_can_send_token(deps.as_ref(), &env, &info, &token_id, &token)?;
+
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if user.is_some() && expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        let user_addr = user
+            .as_deref()
+            .map(|u| deps.api.addr_validate(u))
+            .transpose()?;
+
+        self._clear_user(deps.storage, &token_id)?;
+        if let Some(user) = user_addr {
+            self.user_tokens
+                .save(deps.storage, (&user, &token_id), &expires)?;
+            self.token_users
+                .save(deps.storage, &token_id, &TokenUser { user, expires })?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "set_user")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("user", user.unwrap_or_default()))
+    }
+
+    pub fn prune_expired_approvals(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut pruned = 0;
+        for token_id in token_ids {
+            if let Some(mut token) = self.tokens.may_load(deps.storage, &token_id)? {
+                let removed =
+                    self._prune_approvals(deps.storage, &env.block, &token_id, &mut token)?;
+                if removed > 0 {
+                    self.tokens.save(deps.storage, &token_id, &token)?;
+                    pruned += removed;
+                }
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "prune_expired_approvals")
+            .add_attribute("sender", info.sender)
+            .add_attribute("pruned", pruned.to_string()))
+    }
+
+    pub fn prune_expired_operators(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        owner: String,
+    ) -> Result<Response<C>, ContractError> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let expired = self
+            .operators
+            .prefix(&owner_addr)
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter_map(|r| match r {
+                Ok((operator, expires)) if expires.is_expired(&env.block) => Some(Ok(operator)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        for operator in &expired {
+            self.operators.remove(deps.storage, (&owner_addr, operator));
+            self.operator_grants
+                .remove(deps.storage, (operator, &owner_addr));
+            self.operator_scopes
+                .remove(deps.storage, (&owner_addr, operator));
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "prune_expired_operators")
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("pruned", expired.len().to_string()))
+    }
+
+    pub fn grant_role(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        account: String,
+        mint_quota: Option<u64>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_has_role(deps.as_ref(), &info, Role::Admin)?;
+
+        if role != Role::Minter && mint_quota.is_some() {
+            return Err(StdError::generic_err("mint_quota only applies to the minter role").into());
+        }
+        let account_addr = deps.api.addr_validate(&account)?;
+        self.roles
+            .save(deps.storage, (role.as_str(), &account_addr), &Empty {})?;
+        if role == Role::Minter {
+            let minted = self
+                .mint_quotas
+                .may_load(deps.storage, &account_addr)?
+                .map(|quota| quota.minted)
+                .unwrap_or_default();
+            let quota = MintQuota {
+                limit: mint_quota,
+                minted,
+            };
+            self.mint_quotas.save(deps.storage, &account_addr, &quota)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", info.sender)
+            .add_attribute("role", role.as_str())
+            .add_attribute("account", account))
+    }
+
+    pub fn revoke_role(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        role: Role,
+        account: String,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_has_role(deps.as_ref(), &info, Role::Admin)?;
+
+        let account_addr = deps.api.addr_validate(&account)?;
+        self.roles
+            .remove(deps.storage, (role.as_str(), &account_addr));
+        if role == Role::Minter {
+            self.mint_quotas.remove(deps.storage, &account_addr);
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("sender", info.sender)
+            .add_attribute("role", role.as_str())
+            .add_attribute("account", account))
+    }
+
+    pub fn freeze_minting(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_is_minter(deps.as_ref(), &info)?;
+
+        self.minting_frozen.save(deps.storage, &true)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "freeze_minting")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn clawback(
+        &self,
+        mut deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+        reason: String,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_is_minter(deps.as_ref(), &info)?;
+        if !self.is_clawback_enabled(deps.storage)? {
+            return Err(ContractError::ClawbackDisabled {});
+        }
+
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self._remove_token(deps.branch(), &token_id, &mut token)?;
+        self.decrement_tokens(deps.storage)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "clawback")
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", token.owner)
+            .add_attribute("token_id", token_id)
+            .add_attribute("reason", reason))
+    }
+
+    pub fn disable_clawback(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_is_minter(deps.as_ref(), &info)?;
+
+        self.clawback_enabled.remove(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "disable_clawback")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn batch_transfer_nft(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut res = Response::new()
+            .add_attribute("action", "batch_transfer_nft")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("recipient", recipient.clone());
+        for token_id in token_ids {
+            let token = self._transfer_nft(deps.branch(), &env, &info, &recipient, &token_id)?;
+            self._record_ownership(
+                deps.storage,
+                &env,
+                &token_id,
+                &token.owner,
+                OwnershipAction::Transfer,
+            )?;
+            res = res.add_attribute("token_id", token_id);
+        }
+
+        Ok(res)
+    }
+
+    pub fn batch_send_nft(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        contract: String,
+        token_ids: Vec<String>,
+        msg: Binary,
+    ) -> Result<Response<C>, ContractError> {
+        let mut res = Response::new()
+            .add_attribute("action", "batch_send_nft")
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("recipient", contract.clone());
+        for token_id in token_ids {
+            // Transfer token
+            let token = self._transfer_nft(deps.branch(), &env, &info, &contract, &token_id)?;
+            self._record_ownership(
+                deps.storage,
+                &env,
+                &token_id,
+                &token.owner,
+                OwnershipAction::Send,
+            )?;
+
+            let send = Cw721ReceiveMsg {
+                sender: info.sender.to_string(),
+                token_id: token_id.clone(),
+                msg: msg.clone(),
+            };
+            res = res
+                .add_message(send.into_cosmos_msg(contract.clone())?)
+                .add_attribute("token_id", token_id);
+        }
+
+        Ok(res)
+    }
+
+    pub fn batch_burn(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_ids: Vec<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut res = Response::new()
+            .add_attribute("action", "batch_burn")
+            .add_attribute("sender", info.sender.clone());
+        let amount = token_ids.len() as u64;
+        for token_id in token_ids {
+            self._burn(deps.branch(), &env, &info, &token_id)?;
+            res = res.add_attribute("token_id", token_id);
+        }
+        self.decrease_tokens(deps.storage, amount)?;
+
+        Ok(res)
+    }
+
+    pub fn set_pause_state(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        pause_state: PauseState,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_has_role(deps.as_ref(), &info, Role::Pauser)?;
+
+        self.pause_state.save(deps.storage, &pause_state)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_pause_state")
+            .add_attribute("sender", info.sender)
+            .add_attribute("mint", pause_state.mint.to_string())
+            .add_attribute("transfer", pause_state.transfer.to_string())
+            .add_attribute("approve", pause_state.approve.to_string())
+            .add_attribute("burn", pause_state.burn.to_string()))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_nft_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+        token_uri: Option<String>,
+        clear_token_uri: bool,
+        extension: Option<T>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_update_metadata(deps.as_ref(), &info)?;
+        if clear_token_uri && token_uri.is_some() {
+            return Err(StdError::generic_err("cannot set and clear token_uri at once").into());
+        }
+
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        if clear_token_uri {
+            token.token_uri = None;
+        } else if let Some(token_uri) = token_uri {
+            token.token_uri = Some(token_uri);
+        }
+        if let Some(extension) = extension {
+            token.extension = extension;
+        }
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_nft_info")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn update_collection_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        collection_info: CollectionInfo,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_can_update_collection_info(deps.as_ref(), &info)?;
+
+        let collection_info = validate_collection_info(deps.api, collection_info)?;
+        self.collection_info.save(deps.storage, &collection_info)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "update_collection_info")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn propose_minter_ownership(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        new_minter: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_is_minter(deps.as_ref(), &info)?;
+
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+
+        let pending = PendingMinter {
+            minter: deps.api.addr_validate(&new_minter)?,
+            expires,
+        };
+        self.pending_minter.save(deps.storage, &pending)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "propose_minter_ownership")
+            .add_attribute("sender", info.sender)
+            .add_attribute("pending_minter", new_minter)
+            .add_attribute("expires", expires.to_string()))
+    }
+
+    pub fn accept_minter_ownership(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let pending = self
+            .pending_minter
+            .may_load(deps.storage)?
+            .ok_or(ContractError::NoPendingMinter {})?;
+
+        if pending.minter != info.sender {
+            return Err(ContractError::Unauthorized {});
+        }
+        if pending.expires.is_expired(&env.block) {
+            return Err(ContractError::PendingMinterExpired {});
+        }
+
+        self.minter.save(deps.storage, &pending.minter)?;
+        self.pending_minter.remove(deps.storage);
+        // the minter mints without a quota
+        self.mint_quotas.remove(deps.storage, &pending.minter);
+
+        Ok(Response::new()
+            .add_attribute("action", "accept_minter_ownership")
+            .add_attribute("minter", info.sender))
+    }
+
+    pub fn renounce_minter_ownership(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        self.check_is_minter(deps.as_ref(), &info)?;
+
+        self.minter.remove(deps.storage);
+        self.pending_minter.remove(deps.storage);
+        // roles are granted on behalf of the minter, so they go with it
+        self.roles.clear(deps.storage);
+        self.mint_quotas.clear(deps.storage);
+
+        Ok(Response::new()
+            .add_attribute("action", "renounce_minter_ownership")
+            .add_attribute("sender", info.sender))
     }
 }
 
@@ -131,13 +847,20 @@ where
 
     fn transfer_nft(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         recipient: String,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        self._transfer_nft(deps, &env, &info, &recipient, &token_id)?;
+        let token = self._transfer_nft(deps.branch(), &env, &info, &recipient, &token_id)?;
+        self._record_ownership(
+            deps.storage,
+            &env,
+            &token_id,
+            &token.owner,
+            OwnershipAction::Transfer,
+        )?;
 
         Ok(Response::new()
             .add_attribute("action", "transfer_nft")
@@ -148,7 +871,7 @@ where
 
     fn send_nft(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         contract: String,
@@ -156,7 +879,14 @@ where
         msg: Binary,
     ) -> Result<Response<C>, ContractError> {
         // Transfer token
-        self._transfer_nft(deps, &env, &info, &contract, &token_id)?;
+        let token = self._transfer_nft(deps.branch(), &env, &info, &contract, &token_id)?;
+        self._record_ownership(
+            deps.storage,
+            &env,
+            &token_id,
+            &token.owner,
+            OwnershipAction::Send,
+        )?;
 
         let send = Cw721ReceiveMsg {
             sender: info.sender.to_string(),
@@ -226,6 +956,10 @@ where
         let operator_addr = deps.api.addr_validate(&operator)?;
         self.operators
             .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+        self.operator_grants
+            .save(deps.storage, (&operator_addr, &info.sender), &expires)?;
+        self.operator_scopes
+            .remove(deps.storage, (&info.sender, &operator_addr));
 
         Ok(Response::new()
             .add_attribute("action", "approve_all")
@@ -243,6 +977,10 @@ where
         let operator_addr = deps.api.addr_validate(&operator)?;
         self.operators
             .remove(deps.storage, (&info.sender, &operator_addr));
+        self.operator_grants
+            .remove(deps.storage, (&operator_addr, &info.sender));
+        self.operator_scopes
+            .remove(deps.storage, (&info.sender, &operator_addr));
 
         Ok(Response::new()
             .add_attribute("action", "revoke_all")
@@ -252,15 +990,12 @@ where
 
     fn burn(
         &self,
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
     ) -> Result<Response<C>, ContractError> {
-        let token = self.tokens.load(deps.storage, &token_id)?;
-        self.check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        self.tokens.remove(deps.storage, &token_id)?;
+        self._burn(deps.branch(), &env, &info, &token_id)?;
         self.decrement_tokens(deps.storage)?;
 
         Ok(Response::new()
@@ -278,6 +1013,136 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
+    /// creates a new token, failing if the token_id is already taken.
+    /// The caller is responsible for checking permissions and updating the token count
+    pub fn _mint(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        msg: MintMsg<T>,
+    ) -> Result<TokenInfo<T>, ContractError> {
+        if let Some(rules) = self.token_id_rules.may_load(deps.storage)? {
+            validate_token_id(&rules, &msg.token_id)?;
+        }
+        let token = TokenInfo {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            approvals: vec![],
+            token_uri: msg.token_uri,
+            extension: msg.extension,
+        };
+        let token = self
+            .tokens
+            .update(deps.storage, &msg.token_id, |old| match old {
+                Some(_) => Err(ContractError::Claimed {}),
+                None => Ok(token),
+            })?;
+        self.increment_balance(deps.storage, &token.owner)?;
+        self._record_ownership(
+            deps.storage,
+            env,
+            &msg.token_id,
+            &token.owner,
+            OwnershipAction::Mint,
+        )?;
+        Ok(token)
+    }
+
+    /// adds to the number of tokens minted by an account with the minter role
+    pub fn _count_mints(
+        &self,
+        storage: &mut dyn Storage,
+        minter: &Addr,
+        amount: u64,
+    ) -> StdResult<()> {
+        if let Some(mut quota) = self.mint_quotas.may_load(storage, minter)? {
+            quota.minted += amount;
+            self.mint_quotas.save(storage, minter, &quota)?;
+        }
+        Ok(())
+    }
+
+    /// appends the new owner to the token's history if ownership history is tracked
+    pub fn _record_ownership(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        token_id: &str,
+        owner: &Addr,
+        action: OwnershipAction,
+    ) -> StdResult<()> {
+        if !self.is_tracking_ownership_history(storage)? {
+            return Ok(());
+        }
+
+        let index = match self
+            .ownership_history
+            .prefix(token_id)
+            .keys(storage, None, None, Order::Descending)
+            .next()
+        {
+            Some(last) => last? + 1,
+            None => 0,
+        };
+        let entry = OwnershipEntry {
+            owner: owner.clone(),
+            action,
+            height: env.block.height,
+            time: env.block.time,
+        };
+        self.ownership_history
+            .save(storage, (token_id, index), &entry)
+    }
+
+    /// removes a token the sender has access to.
+    /// The caller is responsible for updating the token count
+    pub fn _burn(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: &str,
+    ) -> Result<TokenInfo<T>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, token_id)?;
+        // ensure we have permissions
+        self.check_can_send_token(deps.as_ref(), env, info, token_id, &token)?;
+        self._remove_token(deps, token_id, &mut token)?;
+        Ok(token)
+    }
+
+    /// removes a token without checking permissions, along with its approvals and user.
+    /// The caller is responsible for updating the token count
+    pub fn _remove_token(
+        &self,
+        deps: DepsMut,
+        token_id: &str,
+        token: &mut TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        self._clear_approvals(deps.storage, token_id, token)?;
+        self._clear_user(deps.storage, token_id)?;
+        self._clear_ownership_history(deps.storage, token_id)?;
+        self.tokens.remove(deps.storage, token_id)?;
+        self.decrement_balance(deps.storage, &token.owner)?;
+        Ok(())
+    }
+
+    /// forgets the owners of a removed token, so a token minted later
+    /// under the same id does not inherit them
+    pub fn _clear_ownership_history(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+    ) -> StdResult<()> {
+        let indexes = self
+            .ownership_history
+            .prefix(token_id)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for index in indexes {
+            self.ownership_history.remove(storage, (token_id, index));
+        }
+        Ok(())
+    }
+
     pub fn _transfer_nft(
         &self,
         deps: DepsMut,
@@ -288,14 +1153,65 @@ where
     ) -> Result<TokenInfo<T>, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
-        self.check_can_send(deps.as_ref(), env, info, &token)?;
+        self.check_can_send_token(deps.as_ref(), env, info, token_id, &token)?;
         // set owner and remove existing approvals
-        token.owner = deps.api.addr_validate(recipient)?;
-        token.approvals = vec![];
+        let recipient = deps.api.addr_validate(recipient)?;
+        if token.owner != recipient {
+            self.decrement_balance(deps.storage, &token.owner)?;
+            self.increment_balance(deps.storage, &recipient)?;
+            self._clear_user(deps.storage, token_id)?;
+        }
+        token.owner = recipient;
+        self._clear_approvals(deps.storage, token_id, &mut token)?;
         self.tokens.save(deps.storage, token_id, &token)?;
         Ok(token)
     }
 
+    /// removes the expired approvals of the token, keeping the spender index in sync.
+    /// Returns how many were removed
+    pub fn _prune_approvals(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        token_id: &str,
+        token: &mut TokenInfo<T>,
+    ) -> StdResult<usize> {
+        let (expired, approvals) = token
+            .approvals
+            .drain(..)
+            .partition::<Vec<_>, _>(|apr| apr.is_expired(block));
+        token.approvals = approvals;
+        for approval in &expired {
+            self.spender_approvals
+                .remove(storage, (&approval.spender, token_id));
+        }
+        Ok(expired.len())
+    }
+
+    /// removes all approvals of the token, keeping the spender index in sync
+    pub fn _clear_approvals(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        token: &mut TokenInfo<T>,
+    ) -> StdResult<()> {
+        for approval in token.approvals.drain(..) {
+            self.spender_approvals
+                .remove(storage, (&approval.spender, token_id));
+        }
+        Ok(())
+    }
+
+    /// removes the user of the token, if any, keeping the user index in sync
+    pub fn _clear_user(&self, storage: &mut dyn Storage, token_id: &str) -> StdResult<()> {
+        if let Some(token_user) = self.token_users.may_load(storage, token_id)? {
+            self.user_tokens
+                .remove(storage, (&token_user.user, token_id));
+            self.token_users.remove(storage, token_id);
+        }
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn _update_approvals(
         &self,
@@ -310,11 +1226,14 @@ where
     ) -> Result<TokenInfo<T>, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
         // ensure we have permissions
-        self.check_can_approve(deps.as_ref(), env, info, &token)?;
+        self.check_can_approve_token(deps.as_ref(), env, info, token_id, &token)?;
 
         // update the approval list (remove any for the same spender before adding)
         let spender_addr = deps.api.addr_validate(spender)?;
         token.approvals.retain(|apr| apr.spender != spender_addr);
+        self.spender_approvals
+            .remove(deps.storage, (&spender_addr, token_id));
+        self._prune_approvals(deps.storage, &env.block, token_id, &mut token)?;
 
         // only difference between approve and revoke
         if add {
@@ -323,6 +1242,13 @@ where
             if expires.is_expired(&env.block) {
                 return Err(ContractError::Expired {});
             }
+            if let Some(max) = self.max_approvals.may_load(deps.storage)? {
+                if token.approvals.len() >= max as usize {
+                    return Err(ContractError::TooManyApprovals { max });
+                }
+            }
+            self.spender_approvals
+                .save(deps.storage, (&spender_addr, token_id), &expires)?;
             let approval = Approval {
                 spender: spender_addr,
                 expires,
@@ -335,40 +1261,209 @@ where
         Ok(token)
     }
 
-    /// returns true iff the sender can execute approve or reject on the contract
+    /// returns true iff the sender is the current minter
+    pub fn check_is_minter(&self, deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+        match self.minter.may_load(deps.storage)? {
+            Some(minter) if minter == info.sender => Ok(()),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
+
+    /// returns an error if the kind of action requested by `msg` is paused
+    pub fn check_not_paused(
+        &self,
+        deps: Deps,
+        msg: &ExecuteMsg<T, E>,
+    ) -> Result<(), ContractError> {
+        let pause_state = self.pause_state(deps.storage)?;
+        let (action, paused) = match msg {
+            ExecuteMsg::Mint(_) | ExecuteMsg::BatchMint { .. } | ExecuteMsg::MintNext { .. } => {
+                ("mint", pause_state.mint)
+            }
+            ExecuteMsg::TransferNft { .. }
+            | ExecuteMsg::SendNft { .. }
+            | ExecuteMsg::BatchTransferNft { .. }
+            | ExecuteMsg::BatchSendNft { .. } => ("transfer", pause_state.transfer),
+            ExecuteMsg::Approve { .. }
+            | ExecuteMsg::ApproveAll { .. }
+            | ExecuteMsg::ApproveAllScoped { .. }
+            | ExecuteMsg::SetUser { .. } => ("approve", pause_state.approve),
+            ExecuteMsg::Burn { .. }
+            | ExecuteMsg::BatchBurn { .. }
+            | ExecuteMsg::Clawback { .. } => ("burn", pause_state.burn),
+            // revoking access and administrative actions are never paused
+            ExecuteMsg::Revoke { .. }
+            | ExecuteMsg::RevokeAll { .. }
+            | ExecuteMsg::PruneExpiredApprovals { .. }
+            | ExecuteMsg::PruneExpiredOperators { .. }
+            | ExecuteMsg::FreezeMinting {}
+            | ExecuteMsg::DisableClawback {}
+            | ExecuteMsg::SetPauseState { .. }
+            | ExecuteMsg::UpdateNftInfo { .. }
+            | ExecuteMsg::UpdateCollectionInfo { .. }
+            | ExecuteMsg::GrantRole { .. }
+            | ExecuteMsg::RevokeRole { .. }
+            | ExecuteMsg::ProposeMinterOwnership { .. }
+            | ExecuteMsg::AcceptMinterOwnership {}
+            | ExecuteMsg::RenounceMinterOwnership {}
+            | ExecuteMsg::Extension { .. } => return Ok(()),
+        };
+
+        if paused {
+            return Err(ContractError::Paused {
+                action: action.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// returns true iff the sender can mint `amount` more tokens
+    pub fn check_can_mint(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        amount: u64,
+    ) -> Result<(), ContractError> {
+        self.check_has_role(deps, info, Role::Minter)?;
+
+        // quotas only cap accounts granted the role, never the contract minter itself
+        let is_minter = self.minter.may_load(deps.storage)?.as_ref() == Some(&info.sender);
+        let quota = if is_minter {
+            None
+        } else {
+            self.mint_quotas.may_load(deps.storage, &info.sender)?
+        };
+        if let Some(quota) = quota {
+            if let Some(limit) = quota.limit {
+                if quota.minted + amount > limit {
+                    return Err(ContractError::MintQuotaExceeded {
+                        remaining: limit.saturating_sub(quota.minted),
+                    });
+                }
+            }
+        }
+        if self.is_minting_frozen(deps.storage)? {
+            return Err(ContractError::MintingFrozen {});
+        }
+        if let Some(max_supply) = self.max_supply.may_load(deps.storage)? {
+            if self.total_minted(deps.storage)? + amount > max_supply {
+                return Err(ContractError::MaxSupplyReached {});
+            }
+        }
+        Ok(())
+    }
+
+    /// returns true iff the sender is the current minter or was granted the role
+    pub fn check_has_role(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        role: Role,
+    ) -> Result<(), ContractError> {
+        if self.has_role(deps.storage, role, &info.sender)? {
+            Ok(())
+        } else {
+            Err(ContractError::Unauthorized {})
+        }
+    }
+
+    /// returns true iff the sender is the current minter or a metadata updater
+    pub fn check_can_update_metadata(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+    ) -> Result<(), ContractError> {
+        self.check_has_role(deps, info, Role::MetadataUpdater)
+    }
+
+    /// returns true iff the sender is the collection's creator or the minter
+    pub fn check_can_update_collection_info(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+    ) -> Result<(), ContractError> {
+        let creator = self
+            .collection_info
+            .may_load(deps.storage)?
+            .and_then(|collection_info| collection_info.creator);
+        if creator.as_deref() == Some(info.sender.as_str()) {
+            return Ok(());
+        }
+        self.check_is_minter(deps, info)
+    }
+
+    /// returns true iff the sender can execute approve or reject on the contract.
+    /// Without the token id, operators limited to some tokens are always rejected,
+    /// use `check_can_approve_token` instead
     pub fn check_can_approve(
         &self,
         deps: Deps,
         env: &Env,
         info: &MessageInfo,
         token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        self.check_approver(deps, env, info, None, token)
+    }
+
+    /// returns true iff the sender can execute approve or reject on the given token
+    pub fn check_can_approve_token(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        self.check_approver(deps, env, info, Some(token_id), token)
+    }
+
+    /// returns true iff the sender can transfer ownership of the token.
+    /// Without the token id, operators limited to some tokens are always rejected,
+    /// use `check_can_send_token` instead
+    pub fn check_can_send(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        self.check_sender(deps, env, info, None, token)
+    }
+
+    /// returns true iff the sender can transfer ownership of the given token
+    pub fn check_can_send_token(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        self.check_sender(deps, env, info, Some(token_id), token)
+    }
+
+    fn check_approver(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: Option<&str>,
+        token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
         // owner can approve
         if token.owner == info.sender {
             return Ok(());
         }
-        // operator can approve
-        let op = self
-            .operators
-            .may_load(deps.storage, (&token.owner, &info.sender))?;
-        match op {
-            Some(ex) => {
-                if ex.is_expired(&env.block) {
-                    Err(ContractError::Unauthorized {})
-                } else {
-                    Ok(())
-                }
-            }
-            None => Err(ContractError::Unauthorized {}),
-        }
+        // operator can approve, unless scoped otherwise
+        self.check_operator(deps, env, info, token_id, token, true)
     }
 
-    /// returns true iff the sender can transfer ownership of the token
-    pub fn check_can_send(
+    fn check_sender(
         &self,
         deps: Deps,
         env: &Env,
         info: &MessageInfo,
+        token_id: Option<&str>,
         token: &TokenInfo<T>,
     ) -> Result<(), ContractError> {
         // owner can send
@@ -385,19 +1480,92 @@ where
             return Ok(());
         }
 
-        // operator can send
+        // operator can send, if the token is in scope
+        self.check_operator(deps, env, info, token_id, token, false)
+    }
+
+    /// returns true iff the sender is an unexpired operator of the token owner
+    /// whose scope covers the token (and approving, if `approve` is set).
+    /// An unknown token id is only covered by scopes not limited to some tokens
+    fn check_operator(
+        &self,
+        deps: Deps,
+        env: &Env,
+        info: &MessageInfo,
+        token_id: Option<&str>,
+        token: &TokenInfo<T>,
+        approve: bool,
+    ) -> Result<(), ContractError> {
         let op = self
             .operators
             .may_load(deps.storage, (&token.owner, &info.sender))?;
         match op {
-            Some(ex) => {
-                if ex.is_expired(&env.block) {
-                    Err(ContractError::Unauthorized {})
-                } else {
-                    Ok(())
+            Some(ex) if !ex.is_expired(&env.block) => {
+                let scope = self
+                    .operator_scopes
+                    .may_load(deps.storage, (&token.owner, &info.sender))?;
+                match scope {
+                    Some(scope) => {
+                        let covered = match token_id {
+                            Some(token_id) => scope.covers(token_id),
+                            None => scope.token_ids.is_none(),
+                        };
+                        if !covered || (approve && !scope.can_approve) {
+                            Err(ContractError::Unauthorized {})
+                        } else {
+                            Ok(())
+                        }
+                    }
+                    None => Ok(()),
                 }
             }
-            None => Err(ContractError::Unauthorized {}),
+            _ => Err(ContractError::Unauthorized {}),
+        }
+    }
+}
+
+/// normalizes the addresses in `collection_info` and checks the royalty share
+fn validate_collection_info(
+    api: &dyn Api,
+    mut collection_info: CollectionInfo,
+) -> StdResult<CollectionInfo> {
+    if let Some(creator) = collection_info.creator {
+        collection_info.creator = Some(api.addr_validate(&creator)?.into_string());
+    }
+    if let Some(royalty_info) = collection_info.royalty_info.as_mut() {
+        royalty_info.payment_address = api
+            .addr_validate(&royalty_info.payment_address)?
+            .into_string();
+        if royalty_info.share > Decimal::one() {
+            return Err(StdError::generic_err(
+                "royalty share must be between 0 and 1",
+            ));
+        }
+    }
+    Ok(collection_info)
+}
+
+/// checks the token id against the contract's token id rules
+fn validate_token_id(rules: &TokenIdRules, token_id: &str) -> Result<(), ContractError> {
+    let invalid = |reason: String| Err(ContractError::InvalidTokenId { reason });
+    if token_id.is_empty() {
+        return invalid("token_id cannot be empty".to_string());
+    }
+    if let Some(max_length) = rules.max_length {
+        if token_id.len() > max_length as usize {
+            return invalid(format!("longer than {} bytes", max_length));
+        }
+    }
+    if rules.numeric_only && !token_id.bytes().all(|b| b.is_ascii_digit()) {
+        return invalid("only digits are allowed".to_string());
+    }
+    if let Some(symbols) = &rules.allowed_symbols {
+        if let Some(c) = token_id
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !symbols.contains(*c))
+        {
+            return invalid(format!("{:?} is not allowed", c));
         }
     }
+    Ok(())
 }
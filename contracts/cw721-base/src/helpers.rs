@@ -6,12 +6,14 @@ use cosmwasm_std::{
 };
 use cw721::{
     AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
-    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+    NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::{ExecuteMsg, QueryMsg};
+use crate::{
+    CollectionInfo, ExecuteMsg, OperatorsWithScopeResponse, QueryMsg, TokensWithInfoResponse,
+};
 
 #[cw_serde]
 pub struct Cw721Contract<Q: CustomMsg, E: CustomMsg>(
@@ -106,10 +108,20 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
             owner: owner.into(),
             include_expired: Some(include_expired),
             start_after,
+            start_before: None,
+            order: None,
             limit,
         };
-        let res: OperatorsResponse = self.query(querier, req)?;
-        Ok(res.operators)
+        let res: OperatorsWithScopeResponse = self.query(querier, req)?;
+        let operators = res
+            .operators
+            .into_iter()
+            .map(|operator| Approval {
+                spender: operator.spender,
+                expires: operator.expires,
+            })
+            .collect();
+        Ok(operators)
     }
 
     pub fn num_tokens(&self, querier: &QuerierWrapper) -> StdResult<u64> {
@@ -124,6 +136,11 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
         self.query(querier, req)
     }
 
+    pub fn collection_info(&self, querier: &QuerierWrapper) -> StdResult<CollectionInfo> {
+        let req = QueryMsg::CollectionInfo {};
+        self.query(querier, req)
+    }
+
     /// With metadata extension
     pub fn nft_info<T: Into<String>, U: DeserializeOwned>(
         &self,
@@ -161,6 +178,8 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
         let req = QueryMsg::Tokens {
             owner: owner.into(),
             start_after,
+            start_before: None,
+            order: None,
             limit,
         };
         self.query(querier, req)
@@ -173,7 +192,39 @@ impl<Q: CustomMsg, E: CustomMsg> Cw721Contract<Q, E> {
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let req = QueryMsg::AllTokens { start_after, limit };
+        let req = QueryMsg::AllTokens {
+            start_after,
+            start_before: None,
+            order: None,
+            limit,
+        };
+        self.query(querier, req)
+    }
+
+    /// With enumerable extension
+    pub fn tokens_with_info<T: Into<String>, U: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        owner: T,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensWithInfoResponse<U>> {
+        let req = QueryMsg::TokensWithInfo {
+            owner: owner.into(),
+            start_after,
+            limit,
+        };
+        self.query(querier, req)
+    }
+
+    /// With enumerable extension
+    pub fn all_tokens_with_info<U: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensWithInfoResponse<U>> {
+        let req = QueryMsg::AllTokensWithInfo { start_after, limit };
         self.query(querier, req)
     }
 
@@ -5,9 +5,21 @@ pub mod helpers;
 pub mod msg;
 mod query;
 pub mod state;
+mod upgrades;
 
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MintMsg, MinterResponse, QueryMsg};
+pub use crate::execute::{CONTRACT_NAME, CONTRACT_VERSION};
+pub use crate::msg::{
+    ApprovedToken, ApprovedTokensResponse, BalanceResponse, ClawbackEnabledResponse,
+    CollectionInfo, CollectionRoyaltyInfo, ExecuteMsg, InstantiateMsg, IsOperatorResponse,
+    MigrateMsg, MintMsg, MintNextResponse, MintQuotaResponse, MinterResponse,
+    MintingFrozenResponse, NextTokenIdResponse, OperatorGrant, OperatorGrantsResponse,
+    OperatorScope, OperatorWithScope, OperatorsWithScopeResponse, OwnershipAction,
+    OwnershipHistoryResponse, OwnershipRecord, PauseStateResponse, PendingMinterResponse,
+    QueryLimitsResponse, QueryMsg, Role, RoleMembersResponse, RolesResponse, SortOrder,
+    SupplyResponse, TokenIdRules, TokenIdRulesResponse, TokenIdSequence, TokenWithInfo,
+    TokensWithInfoResponse, UserOfResponse,
+};
 pub use crate::state::Cw721Contract;
 use cosmwasm_std::Empty;
 
@@ -49,4 +61,10 @@ pub mod entry {
         let tract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
         tract.query(deps, env, msg)
     }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        let tract = Cw721Contract::<Extension, Empty, Empty, Empty>::default();
+        tract.migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+    }
 }
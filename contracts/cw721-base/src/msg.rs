@@ -1,5 +1,5 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Binary;
+use cosmwasm_std::{Binary, Decimal, Order, Timestamp};
 use cw721::Expiration;
 use schemars::JsonSchema;
 
@@ -14,6 +14,139 @@ pub struct InstantiateMsg {
     /// This is designed for a base NFT that is controlled by an external program
     /// or contract. You will likely replace this with custom logic in custom NFTs
     pub minter: String,
+
+    /// Maximum number of tokens that can ever be minted, burned tokens included.
+    /// Unlimited if unset
+    pub max_supply: Option<u64>,
+
+    /// Record every owner of each token so provenance can be queried on-chain,
+    /// disabled if unset
+    pub track_ownership_history: Option<bool>,
+
+    /// Page size of list queries when no limit is given, 10 if unset
+    pub default_limit: Option<u32>,
+    /// Largest page size list queries will return, 100 if unset
+    pub max_limit: Option<u32>,
+
+    /// How `MintNext` numbers tokens, starting at 0 without a prefix if unset
+    pub token_id_sequence: Option<TokenIdSequence>,
+
+    /// Collection level metadata for marketplaces, empty if unset
+    pub collection_info: Option<CollectionInfo>,
+
+    /// Maximum number of unexpired approvals a token can have, unlimited if unset
+    pub max_approvals_per_token: Option<u32>,
+
+    /// Restrictions on minted token ids, any token id is accepted if unset
+    pub token_id_rules: Option<TokenIdRules>,
+
+    /// Let the minter burn any token with `Clawback`, disabled if unset.
+    /// Once disabled it cannot be enabled again
+    pub enable_clawback: Option<bool>,
+}
+
+#[cw_serde]
+pub struct TokenIdSequence {
+    /// Number of the first token minted with `MintNext`
+    pub start: u64,
+    /// Prepended to every number, e.g. "card-" gives "card-1"
+    pub prefix: Option<String>,
+}
+
+/// Restrictions on the token ids that can be minted. Empty token ids are always rejected
+/// once rules are set
+#[cw_serde]
+#[derive(Default)]
+pub struct TokenIdRules {
+    /// Maximum length in bytes, unlimited if unset
+    pub max_length: Option<u32>,
+    /// If set, token ids may only contain ASCII letters, digits and these characters
+    pub allowed_symbols: Option<String>,
+    /// Only accept token ids made of ASCII digits
+    pub numeric_only: bool,
+}
+
+/// Describes the collection as a whole, all fields are optional
+#[cw_serde]
+#[derive(Default)]
+pub struct CollectionInfo {
+    pub description: Option<String>,
+    /// Universal resource identifier of the collection's image
+    pub image: Option<String>,
+    /// Universal resource identifier of a banner image
+    pub banner: Option<String>,
+    pub external_link: Option<String>,
+    /// Account credited with the collection, may also update this info
+    pub creator: Option<String>,
+    /// Royalties expected on secondary sales of the collection's tokens
+    pub royalty_info: Option<CollectionRoyaltyInfo>,
+}
+
+#[cw_serde]
+pub struct CollectionRoyaltyInfo {
+    pub payment_address: String,
+    /// Fraction of the sale price, between 0 and 1
+    pub share: Decimal,
+}
+
+/// Limits an operator approval. Scoped operators can always transfer, send and burn
+/// the tokens in scope
+#[cw_serde]
+pub struct OperatorScope {
+    /// Whether the operator may approve or revoke spenders
+    pub can_approve: bool,
+    /// Restricts the operator to these tokens, all of the owner's tokens if unset
+    pub token_ids: Option<Vec<String>>,
+}
+
+impl OperatorScope {
+    pub fn covers(&self, token_id: &str) -> bool {
+        match &self.token_ids {
+            Some(token_ids) => token_ids.iter().any(|id| id == token_id),
+            None => true,
+        }
+    }
+}
+
+/// Permissions that can be granted to other accounts besides the minter.
+/// The minter implicitly holds every role
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    /// May mint, optionally up to a quota
+    Minter,
+    /// May update token metadata
+    MetadataUpdater,
+    /// May pause and resume actions
+    Pauser,
+    /// May grant and revoke roles
+    Admin,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [
+        Role::Minter,
+        Role::MetadataUpdater,
+        Role::Pauser,
+        Role::Admin,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Minter => "minter",
+            Role::MetadataUpdater => "metadata_updater",
+            Role::Pauser => "pauser",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// Maximum number of entries a pending storage backfill handles in this migration,
+    /// defaults to 100. Migrating again to the same version continues the backfill.
+    pub backfill_limit: Option<u32>,
 }
 
 /// This is like Cw721ExecuteMsg but we add a Mint command for an owner
@@ -30,6 +163,18 @@ pub enum ExecuteMsg<T, E> {
         token_id: String,
         msg: Binary,
     },
+    /// Transfer several tokens to the same account in one message
+    BatchTransferNft {
+        recipient: String,
+        token_ids: Vec<String>,
+    },
+    /// Send several tokens to the same contract in one message. The receiving contract
+    /// gets one `Cw721ReceiveMsg` per token, each carrying the same msg
+    BatchSendNft {
+        contract: String,
+        token_ids: Vec<String>,
+        msg: Binary,
+    },
     /// Allows operator to transfer / send the token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
     Approve {
@@ -45,14 +190,107 @@ pub enum ExecuteMsg<T, E> {
         operator: String,
         expires: Option<Expiration>,
     },
-    /// Remove previously granted ApproveAll permission
+    /// Like ApproveAll, but limits what the operator may do, see `OperatorScope`.
+    /// Replaces any earlier ApproveAll for the same operator
+    ApproveAllScoped {
+        operator: String,
+        expires: Option<Expiration>,
+        scope: OperatorScope,
+    },
+    /// Remove previously granted ApproveAll or ApproveAllScoped permission
     RevokeAll { operator: String },
+    /// Let the user use the token without owning it until expiration, or clear the user if unset.
+    /// Can be called by the owner or anyone approved to send the token. The user is cleared
+    /// once the token is transferred
+    SetUser {
+        token_id: String,
+        user: Option<String>,
+        expires: Option<Expiration>,
+    },
+    /// Drop the expired approvals of the given tokens, can be called by anyone.
+    /// Tokens that do not exist are skipped
+    PruneExpiredApprovals { token_ids: Vec<String> },
+    /// Drop the expired operators of the given owner, can be called by anyone
+    PruneExpiredOperators { owner: String },
 
-    /// Mint a new NFT, can only be called by the contract minter
+    /// Mint a new NFT, can only be called by the contract minter or an account
+    /// with the minter role, within its mint quota
     Mint(MintMsg<T>),
+    /// Mint several NFTs at once, can only be called by the contract minter or an account
+    /// with the minter role, within its mint quota.
+    /// Fails without minting anything if any token_id is already taken
+    BatchMint { mints: Vec<MintMsg<T>> },
+    /// Mint a new NFT with the next token_id of the contract's sequence, can only be called
+    /// by the contract minter or an account with the minter role, within its mint quota.
+    /// Ids that are already taken are skipped and the assigned id is returned as
+    /// `MintNextResponse` in the response data
+    MintNext {
+        owner: String,
+        token_uri: Option<String>,
+        extension: T,
+    },
+    /// Permanently disable minting, can only be called by the contract minter.
+    /// This cannot be undone
+    FreezeMinting {},
 
     /// Burn an NFT the sender has access to
     Burn { token_id: String },
+    /// Burn several NFTs the sender has access to in one message
+    BatchBurn { token_ids: Vec<String> },
+    /// Burn any NFT regardless of its owner, e.g. to revoke a credential. Can only be
+    /// called by the contract minter if clawback was enabled at instantiation.
+    /// The reason is recorded in the events
+    Clawback { token_id: String, reason: String },
+    /// Permanently disable `Clawback`, can only be called by the contract minter.
+    /// This cannot be undone
+    DisableClawback {},
+
+    /// Halt or resume mints, transfers, approvals and burns,
+    /// can only be called by the contract minter or a pauser
+    SetPauseState {
+        mint: bool,
+        transfer: bool,
+        approve: bool,
+        burn: bool,
+    },
+
+    /// Replace the token_uri and/or extension of an existing NFT, can only be called
+    /// by the contract minter or a metadata updater. Unset fields are left untouched
+    UpdateNftInfo {
+        token_id: String,
+        token_uri: Option<String>,
+        /// Remove the token_uri, cannot be combined with a new token_uri
+        clear_token_uri: Option<bool>,
+        extension: Option<T>,
+    },
+    /// Replace the collection level metadata, can only be called by the contract minter
+    /// or the collection's creator
+    UpdateCollectionInfo { collection_info: CollectionInfo },
+
+    /// Grant a role to an account, can only be called by the contract minter or an admin.
+    /// `mint_quota` caps how many tokens a minter can mint and is unlimited if unset.
+    /// Granting the minter role again replaces the quota but keeps counting minted tokens,
+    /// revoking it resets the count
+    GrantRole {
+        role: Role,
+        account: String,
+        mint_quota: Option<u64>,
+    },
+    /// Revoke a role from an account, can only be called by the contract minter or an admin
+    RevokeRole { role: Role, account: String },
+
+    /// Propose a new minter, can only be called by the contract minter.
+    /// Minting authority only moves once the new minter accepts.
+    /// If expiration is set, the proposal must be accepted before then
+    ProposeMinterOwnership {
+        new_minter: String,
+        expires: Option<Expiration>,
+    },
+    /// Accept a pending minter proposal, can only be called by the proposed minter
+    AcceptMinterOwnership {},
+    /// Give up minting authority for good, can only be called by the contract minter.
+    /// Also discards any pending proposal and revokes every granted role
+    RenounceMinterOwnership {},
 
     /// Extension msg
     Extension { msg: E },
@@ -95,23 +333,68 @@ pub enum QueryMsg<Q: JsonSchema> {
         token_id: String,
         include_expired: Option<bool>,
     },
-    /// List all operators that can access all of the owner's tokens
-    #[returns(cw721::OperatorsResponse)]
+    /// List all tokens the spender has been approved to transfer or send
+    #[returns(ApprovedTokensResponse)]
+    ApprovedTokens {
+        spender: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// List all operators that can access the owner's tokens, with the scope of operators
+    /// approved with ApproveAllScoped
+    #[returns(OperatorsWithScopeResponse)]
     AllOperators {
         owner: String,
         /// unset or false will filter out expired items, you must set to true to see them
         include_expired: Option<bool>,
         start_after: Option<String>,
+        /// only return operators sorting before this one
+        start_before: Option<String>,
+        /// ascending if unset
+        order: Option<SortOrder>,
         limit: Option<u32>,
     },
+    /// List all owners that made the operator an operator of their account
+    #[returns(OperatorGrantsResponse)]
+    OperatorGrants {
+        operator: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return when the operator's access to the owner's tokens expires and how it is scoped,
+    /// unset if the operator has no (unexpired) access
+    #[returns(IsOperatorResponse)]
+    IsOperator { owner: String, operator: String },
+    /// Number of tokens held by the given owner
+    #[returns(BalanceResponse)]
+    Balance { owner: String },
     /// Total number of tokens issued
     #[returns(cw721::NumTokensResponse)]
     NumTokens {},
+    /// Number of tokens issued, the maximum supply and how many more can be minted
+    #[returns(SupplyResponse)]
+    Supply {},
+    /// Which kinds of actions are currently paused
+    #[returns(PauseStateResponse)]
+    PauseState {},
+    /// Whether minting has been permanently disabled
+    #[returns(MintingFrozenResponse)]
+    MintingFrozen {},
+    /// Whether the minter can burn any token with `Clawback`
+    #[returns(ClawbackEnabledResponse)]
+    ClawbackEnabled {},
 
     /// With MetaData Extension.
     /// Returns top-level metadata about the contract
     #[returns(cw721::ContractInfoResponse)]
     ContractInfo {},
+    /// Returns the collection level metadata set by the minter or creator
+    #[returns(CollectionInfo)]
+    CollectionInfo {},
     /// With MetaData Extension.
     /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
     /// but directly from the contract
@@ -133,27 +416,288 @@ pub enum QueryMsg<Q: JsonSchema> {
     Tokens {
         owner: String,
         start_after: Option<String>,
+        /// only return tokens sorting before this one
+        start_before: Option<String>,
+        /// ascending if unset
+        order: Option<SortOrder>,
         limit: Option<u32>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
     #[returns(cw721::TokensResponse)]
     AllTokens {
+        start_after: Option<String>,
+        /// only return tokens sorting before this one
+        start_before: Option<String>,
+        /// ascending if unset
+        order: Option<SortOrder>,
+        limit: Option<u32>,
+    },
+    /// Like `Tokens`, but returns the owner, unexpired approvals and metadata of each token
+    #[returns(TokensWithInfoResponse<Q>)]
+    TokensWithInfo {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Like `AllTokens`, but returns the owner, unexpired approvals and metadata of each token
+    #[returns(TokensWithInfoResponse<Q>)]
+    AllTokensWithInfo {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the current user of the given token, unset if there is no (unexpired) user
+    #[returns(UserOfResponse)]
+    UserOf { token_id: String },
+    /// List all tokens the given address is an unexpired user of
+    #[returns(cw721::TokensResponse)]
+    TokensByUser {
+        user: String,
         start_after: Option<String>,
         limit: Option<u32>,
     },
 
+    /// Lists the owners of the given token over time, oldest first, since it was last minted.
+    /// Empty unless ownership history is enabled in `InstantiateMsg`
+    #[returns(OwnershipHistoryResponse)]
+    OwnershipHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Page sizes applied to all list queries
+    #[returns(QueryLimitsResponse)]
+    QueryLimits {},
+
+    /// The token_id the next `MintNext` will assign
+    #[returns(NextTokenIdResponse)]
+    NextTokenId {},
+    /// The restrictions on minted token ids, if any
+    #[returns(TokenIdRulesResponse)]
+    TokenIdRules {},
+
     /// Return the minter
     #[returns(MinterResponse)]
     Minter {},
+    /// List the accounts that were granted the role, not including the minter
+    #[returns(RoleMembersResponse)]
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// List the roles granted to the account
+    #[returns(RolesResponse)]
+    Roles { account: String },
+    /// How many tokens an account with the minter role minted and may still mint,
+    /// error if the account does not have the role
+    #[returns(MintQuotaResponse)]
+    MintQuota { minter: String },
+    /// Return the minter proposed by `ProposeMinterOwnership`, if any
+    #[returns(PendingMinterResponse)]
+    PendingMinter {},
 
     /// Extension query
     #[returns(())]
     Extension { msg: Q },
 }
 
-/// Shows who can mint these tokens
+/// Shows how many more tokens can be minted, unset values mean unlimited
+#[cw_serde]
+pub struct SupplyResponse {
+    /// Number of tokens that currently exist
+    pub count: u64,
+    /// Number of tokens ever minted, burned tokens included
+    pub minted: u64,
+    pub max_supply: Option<u64>,
+    pub remaining: Option<u64>,
+}
+
+/// Direction to page through a list in. `start_after` and `start_before` bound the
+/// range the same way in both directions, so descending pages continue with `start_before`
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl From<SortOrder> for Order {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Ascending => Order::Ascending,
+            SortOrder::Descending => Order::Descending,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct TokenWithInfo<T> {
+    pub token_id: String,
+    pub owner: String,
+    /// Unexpired approvals of this token
+    pub approvals: Vec<cw721::Approval>,
+    pub token_uri: Option<String>,
+    pub extension: T,
+}
+
+#[cw_serde]
+pub struct TokensWithInfoResponse<T> {
+    pub tokens: Vec<TokenWithInfo<T>>,
+}
+
+#[cw_serde]
+pub struct ApprovedToken {
+    pub token_id: String,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct ApprovedTokensResponse {
+    pub tokens: Vec<ApprovedToken>,
+}
+
+#[cw_serde]
+pub struct OperatorGrant {
+    pub owner: String,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct OperatorGrantsResponse {
+    pub grants: Vec<OperatorGrant>,
+}
+
+#[cw_serde]
+pub struct OperatorWithScope {
+    pub spender: String,
+    pub expires: Expiration,
+    /// Set if the operator was approved with ApproveAllScoped, left out otherwise so
+    /// unscoped entries still read as a `cw721::Approval`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<OperatorScope>,
+}
+
+#[cw_serde]
+pub struct OperatorsWithScopeResponse {
+    pub operators: Vec<OperatorWithScope>,
+}
+
+#[cw_serde]
+pub struct IsOperatorResponse {
+    pub expires: Option<Expiration>,
+    /// Set if the operator was approved with ApproveAllScoped
+    pub scope: Option<OperatorScope>,
+}
+
+/// How a token came to its owner
+#[cw_serde]
+#[derive(Copy)]
+pub enum OwnershipAction {
+    Mint,
+    Transfer,
+    Send,
+}
+
+#[cw_serde]
+pub struct OwnershipRecord {
+    /// Position in the token's history, pass it as `start_after` to paginate
+    pub index: u64,
+    pub owner: String,
+    pub action: OwnershipAction,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct OwnershipHistoryResponse {
+    pub history: Vec<OwnershipRecord>,
+}
+
+/// Set as the response data of `MintNext`
+#[cw_serde]
+pub struct MintNextResponse {
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct NextTokenIdResponse {
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct BalanceResponse {
+    pub balance: u64,
+}
+
+#[cw_serde]
+pub struct QueryLimitsResponse {
+    pub default_limit: u32,
+    pub max_limit: u32,
+}
+
+/// Shows which kinds of actions are currently paused
+#[cw_serde]
+pub struct PauseStateResponse {
+    pub mint: bool,
+    pub transfer: bool,
+    pub approve: bool,
+    pub burn: bool,
+}
+
+/// Shows if minting has been permanently disabled
+#[cw_serde]
+pub struct MintingFrozenResponse {
+    pub frozen: bool,
+}
+
+#[cw_serde]
+pub struct ClawbackEnabledResponse {
+    pub enabled: bool,
+}
+
+/// Shows who can mint these tokens, unset once minting has been renounced
 #[cw_serde]
 pub struct MinterResponse {
-    pub minter: String,
+    pub minter: Option<String>,
+}
+
+/// Restrictions on the token ids that can be minted, unset if any token id is accepted
+#[cw_serde]
+pub struct TokenIdRulesResponse {
+    pub rules: Option<TokenIdRules>,
+}
+
+#[cw_serde]
+pub struct UserOfResponse {
+    pub user: Option<String>,
+    pub expires: Option<Expiration>,
+}
+
+#[cw_serde]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub roles: Vec<Role>,
+}
+
+/// Unset values mean unlimited
+#[cw_serde]
+pub struct MintQuotaResponse {
+    pub limit: Option<u64>,
+    pub minted: u64,
+    pub remaining: Option<u64>,
+}
+
+/// Shows who may accept the minter role, and until when
+#[cw_serde]
+pub struct PendingMinterResponse {
+    pub pending_minter: Option<String>,
+    pub expires: Option<Expiration>,
 }
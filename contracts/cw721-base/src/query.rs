@@ -13,12 +13,17 @@ use cw721::{
 use cw_storage_plus::Bound;
 use cw_utils::maybe_addr;
 
-use crate::msg::{MinterResponse, QueryMsg};
+use crate::msg::{
+    ApprovedToken, ApprovedTokensResponse, BalanceResponse, ClawbackEnabledResponse,
+    CollectionInfo, IsOperatorResponse, MintQuotaResponse, MinterResponse, MintingFrozenResponse,
+    NextTokenIdResponse, OperatorGrant, OperatorGrantsResponse, OperatorWithScope,
+    OperatorsWithScopeResponse, OwnershipHistoryResponse, OwnershipRecord, PauseStateResponse,
+    PendingMinterResponse, QueryLimitsResponse, QueryMsg, Role, RoleMembersResponse, RolesResponse,
+    SortOrder, SupplyResponse, TokenIdRulesResponse, TokenWithInfo, TokensWithInfoResponse,
+    UserOfResponse,
+};
 use crate::state::{Approval, Cw721Contract, TokenInfo};
 
-const DEFAULT_LIMIT: u32 = 10;
-const MAX_LIMIT: u32 = 100;
-
 impl<'a, T, C, E, Q> Cw721Query<T> for Cw721Contract<'a, T, C, E, Q>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -67,22 +72,16 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<OperatorsResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start_addr = maybe_addr(deps.api, start_after)?;
-        let start = start_addr.as_ref().map(Bound::exclusive);
-
-        let owner_addr = deps.api.addr_validate(&owner)?;
-        let res: StdResult<Vec<_>> = self
-            .operators
-            .prefix(&owner_addr)
-            .range(deps.storage, start, None, Order::Ascending)
-            .filter(|r| {
-                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
-            })
-            .take(limit)
-            .map(parse_approval)
-            .collect();
-        Ok(OperatorsResponse { operators: res? })
+        self.operators_in_range(
+            deps,
+            env,
+            owner,
+            include_expired,
+            start_after,
+            None,
+            SortOrder::Ascending,
+            limit,
+        )
     }
 
     fn approval(
@@ -153,20 +152,7 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
-
-        let owner_addr = deps.api.addr_validate(&owner)?;
-        let tokens: Vec<String> = self
-            .tokens
-            .idx
-            .owner
-            .prefix(owner_addr)
-            .keys(deps.storage, start, None, Order::Ascending)
-            .take(limit)
-            .collect::<StdResult<Vec<_>>>()?;
-
-        Ok(TokensResponse { tokens })
+        self.tokens_in_range(deps, owner, start_after, None, SortOrder::Ascending, limit)
     }
 
     fn all_tokens(
@@ -175,17 +161,7 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
-
-        let tokens: StdResult<Vec<String>> = self
-            .tokens
-            .range(deps.storage, start, None, Order::Ascending)
-            .take(limit)
-            .map(|item| item.map(|(k, _)| k))
-            .collect();
-
-        Ok(TokensResponse { tokens: tokens? })
+        self.all_tokens_in_range(deps, start_after, None, SortOrder::Ascending, limit)
     }
 
     fn all_nft_info(
@@ -216,17 +192,466 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
+    /// like `operators`, but can also page backwards
+    #[allow(clippy::too_many_arguments)]
+    pub fn operators_in_range(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        start_before: Option<String>,
+        order: SortOrder,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorsResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+        let end_addr = maybe_addr(deps.api, start_before)?;
+        let end = end_addr.as_ref().map(Bound::exclusive);
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let res: StdResult<Vec<_>> = self
+            .operators
+            .prefix(&owner_addr)
+            .range(deps.storage, start, end, order.into())
+            .filter(|r| {
+                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+            })
+            .take(limit)
+            .map(parse_approval)
+            .collect();
+        Ok(OperatorsResponse { operators: res? })
+    }
+
+    /// like `operators_in_range`, but also returns the scope of every operator
+    #[allow(clippy::too_many_arguments)]
+    pub fn operators_with_scope(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        start_before: Option<String>,
+        order: SortOrder,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorsWithScopeResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let res = self.operators_in_range(
+            deps,
+            env,
+            owner,
+            include_expired,
+            start_after,
+            start_before,
+            order,
+            limit,
+        )?;
+
+        let operators = res
+            .operators
+            .into_iter()
+            .map(|approval| {
+                let operator = Addr::unchecked(&approval.spender);
+                let scope = self
+                    .operator_scopes
+                    .may_load(deps.storage, (&owner_addr, &operator))?;
+                Ok(OperatorWithScope {
+                    spender: approval.spender,
+                    expires: approval.expires,
+                    scope,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(OperatorsWithScopeResponse { operators })
+    }
+
+    /// like `tokens`, but can also page backwards
+    pub fn tokens_in_range(
+        &self,
+        deps: Deps,
+        owner: String,
+        start_after: Option<String>,
+        start_before: Option<String>,
+        order: SortOrder,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+        let end = start_before.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let tokens: Vec<String> = self
+            .tokens
+            .idx
+            .owner
+            .prefix(owner_addr)
+            .keys(deps.storage, start, end, order.into())
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(TokensResponse { tokens })
+    }
+
+    /// like `all_tokens`, but can also page backwards
+    pub fn all_tokens_in_range(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        start_before: Option<String>,
+        order: SortOrder,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+        let end = start_before.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let tokens: StdResult<Vec<String>> = self
+            .tokens
+            .range(deps.storage, start, end, order.into())
+            .take(limit)
+            .map(|item| item.map(|(k, _)| k))
+            .collect();
+
+        Ok(TokensResponse { tokens: tokens? })
+    }
+
+    pub fn balance_of(&self, deps: Deps, owner: String) -> StdResult<BalanceResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let balance = self.balance(deps.storage, &owner_addr)?;
+        Ok(BalanceResponse { balance })
+    }
+
+    pub fn next_token_id_info(&self, deps: Deps) -> StdResult<NextTokenIdResponse> {
+        let counter = self.next_token_id(deps.storage)?;
+        Ok(NextTokenIdResponse {
+            token_id: counter.token_id(),
+        })
+    }
+
+    pub fn query_limits_info(&self, deps: Deps) -> StdResult<QueryLimitsResponse> {
+        let limits = self.query_limits(deps.storage)?;
+        Ok(QueryLimitsResponse {
+            default_limit: limits.default_limit,
+            max_limit: limits.max_limit,
+        })
+    }
+
+    pub fn token_id_rules_info(&self, deps: Deps) -> StdResult<TokenIdRulesResponse> {
+        Ok(TokenIdRulesResponse {
+            rules: self.token_id_rules.may_load(deps.storage)?,
+        })
+    }
+
+    pub fn collection_info(&self, deps: Deps) -> StdResult<CollectionInfo> {
+        Ok(self
+            .collection_info
+            .may_load(deps.storage)?
+            .unwrap_or_default())
+    }
+
     pub fn minter(&self, deps: Deps) -> StdResult<MinterResponse> {
-        let minter_addr = self.minter.load(deps.storage)?;
+        let minter_addr = self.minter.may_load(deps.storage)?;
         Ok(MinterResponse {
-            minter: minter_addr.to_string(),
+            minter: minter_addr.map(|addr| addr.to_string()),
         })
     }
 
+    pub fn role_members(
+        &self,
+        deps: Deps,
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RoleMembersResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let members = self
+            .roles
+            .prefix(role.as_str())
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(Addr::into_string))
+            .collect::<StdResult<_>>()?;
+
+        Ok(RoleMembersResponse { members })
+    }
+
+    pub fn roles_of(&self, deps: Deps, account: String) -> StdResult<RolesResponse> {
+        let account_addr = deps.api.addr_validate(&account)?;
+        let mut roles = vec![];
+        for role in Role::ALL {
+            if self.has_role(deps.storage, role, &account_addr)? {
+                roles.push(role);
+            }
+        }
+        Ok(RolesResponse { roles })
+    }
+
+    pub fn mint_quota(&self, deps: Deps, minter: String) -> StdResult<MintQuotaResponse> {
+        let minter_addr = deps.api.addr_validate(&minter)?;
+        let quota = self.mint_quotas.load(deps.storage, &minter_addr)?;
+        Ok(MintQuotaResponse {
+            limit: quota.limit,
+            minted: quota.minted,
+            remaining: quota.limit.map(|limit| limit.saturating_sub(quota.minted)),
+        })
+    }
+
+    pub fn pending_minter(&self, deps: Deps) -> StdResult<PendingMinterResponse> {
+        let pending = self.pending_minter.may_load(deps.storage)?;
+        Ok(PendingMinterResponse {
+            pending_minter: pending.as_ref().map(|p| p.minter.to_string()),
+            expires: pending.map(|p| p.expires),
+        })
+    }
+
+    pub fn supply(&self, deps: Deps) -> StdResult<SupplyResponse> {
+        let count = self.token_count(deps.storage)?;
+        let minted = self.total_minted(deps.storage)?;
+        let max_supply = self.max_supply.may_load(deps.storage)?;
+        Ok(SupplyResponse {
+            count,
+            minted,
+            max_supply,
+            remaining: max_supply.map(|max| max.saturating_sub(minted)),
+        })
+    }
+
+    pub fn pause_state_info(&self, deps: Deps) -> StdResult<PauseStateResponse> {
+        let pause_state = self.pause_state(deps.storage)?;
+        Ok(PauseStateResponse {
+            mint: pause_state.mint,
+            transfer: pause_state.transfer,
+            approve: pause_state.approve,
+            burn: pause_state.burn,
+        })
+    }
+
+    pub fn minting_frozen(&self, deps: Deps) -> StdResult<MintingFrozenResponse> {
+        Ok(MintingFrozenResponse {
+            frozen: self.is_minting_frozen(deps.storage)?,
+        })
+    }
+
+    pub fn clawback_enabled(&self, deps: Deps) -> StdResult<ClawbackEnabledResponse> {
+        Ok(ClawbackEnabledResponse {
+            enabled: self.is_clawback_enabled(deps.storage)?,
+        })
+    }
+
+    pub fn tokens_with_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensWithInfoResponse<T>> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let tokens = self
+            .tokens
+            .idx
+            .owner
+            .prefix(owner_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(token_id, info)| token_with_info(&env.block, token_id, info)))
+            .collect::<StdResult<_>>()?;
+
+        Ok(TokensWithInfoResponse { tokens })
+    }
+
+    pub fn all_tokens_with_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensWithInfoResponse<T>> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let tokens = self
+            .tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(token_id, info)| token_with_info(&env.block, token_id, info)))
+            .collect::<StdResult<_>>()?;
+
+        Ok(TokensWithInfoResponse { tokens })
+    }
+
+    pub fn approved_tokens(
+        &self,
+        deps: Deps,
+        env: Env,
+        spender: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ApprovedTokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let spender_addr = deps.api.addr_validate(&spender)?;
+        let tokens = self
+            .spender_approvals
+            .prefix(&spender_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|r| {
+                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+            })
+            .take(limit)
+            .map(|item| item.map(|(token_id, expires)| ApprovedToken { token_id, expires }))
+            .collect::<StdResult<_>>()?;
+
+        Ok(ApprovedTokensResponse { tokens })
+    }
+
+    pub fn user_of(&self, deps: Deps, env: Env, token_id: String) -> StdResult<UserOfResponse> {
+        let token_user = self
+            .token_users
+            .may_load(deps.storage, &token_id)?
+            .filter(|token_user| !token_user.is_expired(&env.block));
+        Ok(UserOfResponse {
+            user: token_user.as_ref().map(|u| u.user.to_string()),
+            expires: token_user.map(|u| u.expires),
+        })
+    }
+
+    pub fn tokens_by_user(
+        &self,
+        deps: Deps,
+        env: Env,
+        user: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let user_addr = deps.api.addr_validate(&user)?;
+        let tokens = self
+            .user_tokens
+            .prefix(&user_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|r| r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block))
+            .take(limit)
+            .map(|item| item.map(|(token_id, _)| token_id))
+            .collect::<StdResult<_>>()?;
+
+        Ok(TokensResponse { tokens })
+    }
+
+    pub fn operator_grants(
+        &self,
+        deps: Deps,
+        env: Env,
+        operator: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorGrantsResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let grants = self
+            .operator_grants
+            .prefix(&operator_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|r| {
+                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+            })
+            .take(limit)
+            .map(|item| {
+                item.map(|(owner, expires)| OperatorGrant {
+                    owner: owner.into_string(),
+                    expires,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(OperatorGrantsResponse { grants })
+    }
+
+    pub fn is_operator(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        operator: String,
+    ) -> StdResult<IsOperatorResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let expires = self
+            .operators
+            .may_load(deps.storage, (&owner_addr, &operator_addr))?
+            .filter(|expires| !expires.is_expired(&env.block));
+        let scope = match expires {
+            Some(_) => self
+                .operator_scopes
+                .may_load(deps.storage, (&owner_addr, &operator_addr))?,
+            None => None,
+        };
+
+        Ok(IsOperatorResponse { expires, scope })
+    }
+
+    pub fn ownership_history(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<OwnershipHistoryResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let history = self
+            .ownership_history
+            .prefix(&token_id)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(index, entry)| OwnershipRecord {
+                    index,
+                    owner: entry.owner.into_string(),
+                    action: entry.action,
+                    height: entry.height,
+                    time: entry.time,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok(OwnershipHistoryResponse { history })
+    }
+
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg<Q>) -> StdResult<Binary> {
         match msg {
             QueryMsg::Minter {} => to_binary(&self.minter(deps)?),
+            QueryMsg::RoleMembers {
+                role,
+                start_after,
+                limit,
+            } => to_binary(&self.role_members(deps, role, start_after, limit)?),
+            QueryMsg::Roles { account } => to_binary(&self.roles_of(deps, account)?),
+            QueryMsg::MintQuota { minter } => to_binary(&self.mint_quota(deps, minter)?),
+            QueryMsg::NextTokenId {} => to_binary(&self.next_token_id_info(deps)?),
+            QueryMsg::TokenIdRules {} => to_binary(&self.token_id_rules_info(deps)?),
+            QueryMsg::QueryLimits {} => to_binary(&self.query_limits_info(deps)?),
+            QueryMsg::PendingMinter {} => to_binary(&self.pending_minter(deps)?),
             QueryMsg::ContractInfo {} => to_binary(&self.contract_info(deps)?),
+            QueryMsg::CollectionInfo {} => to_binary(&self.collection_info(deps)?),
             QueryMsg::NftInfo { token_id } => to_binary(&self.nft_info(deps, token_id)?),
             QueryMsg::OwnerOf {
                 token_id,
@@ -247,24 +672,94 @@ where
                 owner,
                 include_expired,
                 start_after,
+                start_before,
+                order,
                 limit,
-            } => to_binary(&self.operators(
+            } => to_binary(&self.operators_with_scope(
                 deps,
                 env,
                 owner,
                 include_expired.unwrap_or(false),
                 start_after,
+                start_before,
+                order.unwrap_or_default(),
                 limit,
             )?),
+            QueryMsg::ApprovedTokens {
+                spender,
+                include_expired,
+                start_after,
+                limit,
+            } => to_binary(&self.approved_tokens(
+                deps,
+                env,
+                spender,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
+            )?),
+            QueryMsg::OperatorGrants {
+                operator,
+                include_expired,
+                start_after,
+                limit,
+            } => to_binary(&self.operator_grants(
+                deps,
+                env,
+                operator,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
+            )?),
+            QueryMsg::IsOperator { owner, operator } => {
+                to_binary(&self.is_operator(deps, env, owner, operator)?)
+            }
+            QueryMsg::Balance { owner } => to_binary(&self.balance_of(deps, owner)?),
             QueryMsg::NumTokens {} => to_binary(&self.num_tokens(deps)?),
+            QueryMsg::Supply {} => to_binary(&self.supply(deps)?),
+            QueryMsg::MintingFrozen {} => to_binary(&self.minting_frozen(deps)?),
+            QueryMsg::ClawbackEnabled {} => to_binary(&self.clawback_enabled(deps)?),
+            QueryMsg::PauseState {} => to_binary(&self.pause_state_info(deps)?),
             QueryMsg::Tokens {
                 owner,
                 start_after,
+                start_before,
+                order,
+                limit,
+            } => to_binary(&self.tokens_in_range(
+                deps,
+                owner,
+                start_after,
+                start_before,
+                order.unwrap_or_default(),
+                limit,
+            )?),
+            QueryMsg::AllTokens {
+                start_after,
+                start_before,
+                order,
+                limit,
+            } => to_binary(&self.all_tokens_in_range(
+                deps,
+                start_after,
+                start_before,
+                order.unwrap_or_default(),
                 limit,
-            } => to_binary(&self.tokens(deps, owner, start_after, limit)?),
-            QueryMsg::AllTokens { start_after, limit } => {
-                to_binary(&self.all_tokens(deps, start_after, limit)?)
+            )?),
+            QueryMsg::TokensWithInfo {
+                owner,
+                start_after,
+                limit,
+            } => to_binary(&self.tokens_with_info(deps, env, owner, start_after, limit)?),
+            QueryMsg::AllTokensWithInfo { start_after, limit } => {
+                to_binary(&self.all_tokens_with_info(deps, env, start_after, limit)?)
             }
+            QueryMsg::UserOf { token_id } => to_binary(&self.user_of(deps, env, token_id)?),
+            QueryMsg::TokensByUser {
+                user,
+                start_after,
+                limit,
+            } => to_binary(&self.tokens_by_user(deps, env, user, start_after, limit)?),
             QueryMsg::Approval {
                 token_id,
                 spender,
@@ -276,6 +771,11 @@ where
                 spender,
                 include_expired.unwrap_or(false),
             )?),
+            QueryMsg::OwnershipHistory {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.ownership_history(deps, token_id, start_after, limit)?),
             QueryMsg::Approvals {
                 token_id,
                 include_expired,
@@ -294,6 +794,16 @@ fn parse_approval(item: StdResult<(Addr, Expiration)>) -> StdResult<cw721::Appro
     })
 }
 
+fn token_with_info<T>(block: &BlockInfo, token_id: String, info: TokenInfo<T>) -> TokenWithInfo<T> {
+    TokenWithInfo {
+        token_id,
+        owner: info.owner.to_string(),
+        approvals: humanize_approvals(block, &info, false),
+        token_uri: info.token_uri,
+        extension: info.extension,
+    }
+}
+
 fn humanize_approvals<T>(
     block: &BlockInfo,
     info: &TokenInfo<T>,
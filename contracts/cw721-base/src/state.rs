@@ -3,11 +3,13 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage};
+use cosmwasm_std::{Addr, BlockInfo, CustomMsg, Empty, StdResult, Storage, Timestamp};
 
 use cw721::{ContractInfoResponse, Cw721, Expiration};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
+use crate::msg::{CollectionInfo, OperatorScope, OwnershipAction, Role, TokenIdRules};
+
 pub struct Cw721Contract<'a, T, C, E, Q>
 where
     T: Serialize + DeserializeOwned + Clone,
@@ -15,11 +17,55 @@ where
     E: CustomMsg,
 {
     pub contract_info: Item<'a, ContractInfoResponse>,
+    /// Collection level metadata, addresses are validated before saving
+    pub collection_info: Item<'a, CollectionInfo>,
     pub minter: Item<'a, Addr>,
+    /// Minter proposed by the current minter, waiting to accept ownership
+    pub pending_minter: Item<'a, PendingMinter>,
+    /// Stored as (role, account), the accounts granted a role besides the minter
+    pub roles: Map<'a, (&'a str, &'a Addr), Empty>,
+    /// Quota and number of minted tokens of every account with the minter role
+    pub mint_quotas: Map<'a, &'a Addr, MintQuota>,
     pub token_count: Item<'a, u64>,
+    /// Number of tokens ever minted, unlike `token_count` it never goes down
+    pub total_minted: Item<'a, u64>,
+    /// Maximum number of tokens that can ever be minted, unlimited if unset
+    pub max_supply: Item<'a, u64>,
+    /// Next number `MintNext` will try, and the prefix of its token ids
+    pub token_id_sequence: Item<'a, TokenIdCounter>,
+    /// Restrictions on minted token ids, any token id is accepted if unset
+    pub token_id_rules: Item<'a, TokenIdRules>,
+    /// Set once the minter permanently disabled minting
+    pub minting_frozen: Item<'a, bool>,
+    /// Set while the minter may burn any token, removed for good once disabled
+    pub clawback_enabled: Item<'a, bool>,
+    /// Which kinds of actions are currently halted by the minter
+    pub pause_state: Item<'a, PauseState>,
+    /// Page sizes of list queries, the defaults apply if unset
+    pub query_limits: Item<'a, QueryLimits>,
+    /// Set at instantiation if every change of ownership should be recorded
+    pub track_ownership_history: Item<'a, bool>,
+    /// Stored as (token_id, index), the last index holds the current owner
+    pub ownership_history: Map<'a, (&'a str, u64), OwnershipEntry>,
+    /// Maximum number of approvals per token, unlimited if unset
+    pub max_approvals: Item<'a, u32>,
     /// Stored as (granter, operator) giving operator full control over granter's account
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
+    /// Stored as (granter, operator), limits of operators approved with ApproveAllScoped
+    pub operator_scopes: Map<'a, (&'a Addr, &'a Addr), OperatorScope>,
+    /// Stored as (operator, granter), mirrors `operators` so grants can be listed by operator
+    pub operator_grants: Map<'a, (&'a Addr, &'a Addr), Expiration>,
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
+    /// Number of tokens held by each owner, removed once it drops to zero
+    pub balances: Map<'a, &'a Addr, u64>,
+    /// Stored as (spender, token_id), mirrors `TokenInfo.approvals` so approvals can be listed by spender
+    pub spender_approvals: Map<'a, (&'a Addr, &'a str), Expiration>,
+    /// Account allowed to use a token without owning it, cleared on transfer
+    pub token_users: Map<'a, &'a str, TokenUser>,
+    /// Stored as (user, token_id), mirrors `token_users` so tokens can be listed by user
+    pub user_tokens: Map<'a, (&'a Addr, &'a str), Expiration>,
+    /// Where a migration backfill continues, removed once it completed
+    pub pending_backfill: Item<'a, Backfill>,
 
     pub(crate) _custom_response: PhantomData<C>,
     pub(crate) _custom_query: PhantomData<Q>,
@@ -45,11 +91,33 @@ where
     fn default() -> Self {
         Self::new(
             "nft_info",
+            "collection_info",
             "minter",
+            "pending_minter",
+            "roles",
+            "mint_quotas",
             "num_tokens",
+            "total_minted",
+            "max_supply",
+            "token_id_sequence",
+            "token_id_rules",
+            "minting_frozen",
+            "clawback_enabled",
+            "pause_state",
+            "query_limits",
+            "track_ownership_history",
+            "ownership_history",
+            "max_approvals",
             "operators",
+            "operator_scopes",
+            "operators__operator",
             "tokens",
             "tokens__owner",
+            "tokens__spender",
+            "balances",
+            "token_users",
+            "token_users__user",
+            "pending_backfill",
         )
     }
 }
@@ -60,23 +128,68 @@ where
     E: CustomMsg,
     Q: CustomMsg,
 {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         contract_key: &'a str,
+        collection_info_key: &'a str,
         minter_key: &'a str,
+        pending_minter_key: &'a str,
+        roles_key: &'a str,
+        mint_quotas_key: &'a str,
         token_count_key: &'a str,
+        total_minted_key: &'a str,
+        max_supply_key: &'a str,
+        token_id_sequence_key: &'a str,
+        token_id_rules_key: &'a str,
+        minting_frozen_key: &'a str,
+        clawback_enabled_key: &'a str,
+        pause_state_key: &'a str,
+        query_limits_key: &'a str,
+        track_ownership_history_key: &'a str,
+        ownership_history_key: &'a str,
+        max_approvals_key: &'a str,
         operator_key: &'a str,
+        operator_scopes_key: &'a str,
+        operator_grants_key: &'a str,
         tokens_key: &'a str,
         tokens_owner_key: &'a str,
+        tokens_spender_key: &'a str,
+        balances_key: &'a str,
+        token_users_key: &'a str,
+        user_tokens_key: &'a str,
+        pending_backfill_key: &'a str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
         };
         Self {
             contract_info: Item::new(contract_key),
+            collection_info: Item::new(collection_info_key),
             minter: Item::new(minter_key),
+            pending_minter: Item::new(pending_minter_key),
+            roles: Map::new(roles_key),
+            mint_quotas: Map::new(mint_quotas_key),
             token_count: Item::new(token_count_key),
+            total_minted: Item::new(total_minted_key),
+            max_supply: Item::new(max_supply_key),
+            token_id_sequence: Item::new(token_id_sequence_key),
+            token_id_rules: Item::new(token_id_rules_key),
+            minting_frozen: Item::new(minting_frozen_key),
+            clawback_enabled: Item::new(clawback_enabled_key),
+            pause_state: Item::new(pause_state_key),
+            query_limits: Item::new(query_limits_key),
+            track_ownership_history: Item::new(track_ownership_history_key),
+            ownership_history: Map::new(ownership_history_key),
+            max_approvals: Item::new(max_approvals_key),
             operators: Map::new(operator_key),
+            operator_scopes: Map::new(operator_scopes_key),
+            operator_grants: Map::new(operator_grants_key),
             tokens: IndexedMap::new(tokens_key, indexes),
+            spender_approvals: Map::new(tokens_spender_key),
+            balances: Map::new(balances_key),
+            token_users: Map::new(token_users_key),
+            user_tokens: Map::new(user_tokens_key),
+            pending_backfill: Item::new(pending_backfill_key),
             _custom_response: PhantomData,
             _custom_execute: PhantomData,
             _custom_query: PhantomData,
@@ -87,8 +200,94 @@ where
         Ok(self.token_count.may_load(storage)?.unwrap_or_default())
     }
 
+    /// Contracts from before `total_minted` existed start counting from the current token count
+    pub fn total_minted(&self, storage: &dyn Storage) -> StdResult<u64> {
+        match self.total_minted.may_load(storage)? {
+            Some(minted) => Ok(minted),
+            None => self.token_count(storage),
+        }
+    }
+
+    /// returns the counter positioned at the next unclaimed token id
+    pub fn next_token_id(&self, storage: &dyn Storage) -> StdResult<TokenIdCounter> {
+        let mut counter = self
+            .token_id_sequence
+            .may_load(storage)?
+            .unwrap_or_default();
+        while self.tokens.has(storage, &counter.token_id()) {
+            counter.next += 1;
+        }
+        Ok(counter)
+    }
+
+    pub fn is_minting_frozen(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.minting_frozen.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn is_clawback_enabled(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.clawback_enabled.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn pause_state(&self, storage: &dyn Storage) -> StdResult<PauseState> {
+        Ok(self.pause_state.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn query_limits(&self, storage: &dyn Storage) -> StdResult<QueryLimits> {
+        Ok(self.query_limits.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// the number of items to return for a requested page size
+    pub fn page_limit(&self, storage: &dyn Storage, limit: Option<u32>) -> StdResult<usize> {
+        let limits = self.query_limits(storage)?;
+        Ok(limit.unwrap_or(limits.default_limit).min(limits.max_limit) as usize)
+    }
+
+    /// returns true iff the account is the minter or was granted the role
+    pub fn has_role(&self, storage: &dyn Storage, role: Role, account: &Addr) -> StdResult<bool> {
+        if self.minter.may_load(storage)?.as_ref() == Some(account) {
+            return Ok(true);
+        }
+        Ok(self.roles.has(storage, (role.as_str(), account)))
+    }
+
+    pub fn is_tracking_ownership_history(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self
+            .track_ownership_history
+            .may_load(storage)?
+            .unwrap_or_default())
+    }
+
+    pub fn balance(&self, storage: &dyn Storage, owner: &Addr) -> StdResult<u64> {
+        Ok(self.balances.may_load(storage, owner)?.unwrap_or_default())
+    }
+
+    pub fn increment_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        let val = self.balance(storage, owner)? + 1;
+        self.balances.save(storage, owner, &val)?;
+        Ok(val)
+    }
+
+    pub fn decrement_balance(&self, storage: &mut dyn Storage, owner: &Addr) -> StdResult<u64> {
+        // balances may still be backfilled after a migration, so they can be missing here
+        let val = self.balance(storage, owner)?.saturating_sub(1);
+        if val == 0 {
+            self.balances.remove(storage, owner);
+        } else {
+            self.balances.save(storage, owner, &val)?;
+        }
+        Ok(val)
+    }
+
+    /// counts a newly minted token
     pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
-        let val = self.token_count(storage)? + 1;
+        self.increase_tokens(storage, 1)
+    }
+
+    /// counts `amount` newly minted tokens
+    pub fn increase_tokens(&self, storage: &mut dyn Storage, amount: u64) -> StdResult<u64> {
+        let minted = self.total_minted(storage)? + amount;
+        self.total_minted.save(storage, &minted)?;
+        let val = self.token_count(storage)? + amount;
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
@@ -98,6 +297,12 @@ where
         self.token_count.save(storage, &val)?;
         Ok(val)
     }
+
+    pub fn decrease_tokens(&self, storage: &mut dyn Storage, amount: u64) -> StdResult<u64> {
+        let val = self.token_count(storage)? - amount;
+        self.token_count.save(storage, &val)?;
+        Ok(val)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -116,6 +321,102 @@ pub struct TokenInfo<T> {
     pub extension: T,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct TokenIdCounter {
+    /// Number of the next token id to try
+    pub next: u64,
+    pub prefix: String,
+}
+
+impl TokenIdCounter {
+    pub fn token_id(&self) -> String {
+        format!("{}{}", self.prefix, self.next)
+    }
+}
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryLimits {
+    /// Page size used when a query gives no limit
+    pub default_limit: u32,
+    /// Upper bound for the limit a query can ask for
+    pub max_limit: u32,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        QueryLimits {
+            default_limit: DEFAULT_LIMIT,
+            max_limit: MAX_LIMIT,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PauseState {
+    /// Halts Mint and BatchMint
+    pub mint: bool,
+    /// Halts TransferNft, SendNft and their batch variants
+    pub transfer: bool,
+    /// Halts Approve and ApproveAll, revoking is always possible
+    pub approve: bool,
+    /// Halts Burn and BatchBurn
+    pub burn: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnershipEntry {
+    /// Account that became the owner
+    pub owner: Addr,
+    pub action: OwnershipAction,
+    /// Block in which the token changed hands
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// The step of a migration backfill still to be done, and the last key it handled
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub enum Backfill {
+    /// Indexing the approvals of every token after `start_after`
+    SpenderApprovals { start_after: Option<String> },
+    /// Indexing every (granter, operator) pair after `start_after`
+    OperatorGrants { start_after: Option<(Addr, Addr)> },
+    /// Counting the tokens of every owner after `start_after`
+    Balances { start_after: Option<Addr> },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintQuota {
+    /// Maximum number of tokens the account may mint, unlimited if unset
+    pub limit: Option<u64>,
+    /// Number of tokens minted since the role was granted
+    pub minted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenUser {
+    /// Account that can use the token
+    pub user: Addr,
+    /// When the user loses access (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+impl TokenUser {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingMinter {
+    /// Account that can accept the minter role
+    pub minter: Addr,
+    /// When the proposal expires (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct Approval {
     /// Account that can transfer/send the token
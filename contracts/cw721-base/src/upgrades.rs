@@ -0,0 +1,201 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use cosmwasm_std::{Addr, CustomMsg, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+use semver::Version;
+
+use crate::error::ContractError;
+use crate::state::{Backfill, Cw721Contract};
+
+const DEFAULT_BACKFILL_LIMIT: u32 = 100;
+
+/// Brings storage written by `from_version` up to the layout used by this release.
+///
+/// Every layout change gets a step here, guarded by the first release that ships it,
+/// so a contract skipping several releases replays all steps in order. Steps that have
+/// to visit every token only queue a `Backfill`, which `backfill` then works through
+/// a page at a time.
+pub fn migrate<T, C, E, Q>(
+    contract: &Cw721Contract<T, C, E, Q>,
+    storage: &mut dyn Storage,
+    from_version: &Version,
+) -> Result<(), ContractError>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    if *from_version < Version::new(0, 17, 0) {
+        // indexes the spender approvals and operator grants, then counts balances
+        contract
+            .pending_backfill
+            .save(storage, &Backfill::SpenderApprovals { start_after: None })?;
+    }
+    Ok(())
+}
+
+/// Handles up to `limit` entries of the pending backfill, if any.
+/// Returns whether the backfill completed, or `None` if nothing was pending.
+pub fn backfill<T, C, E, Q>(
+    contract: &Cw721Contract<T, C, E, Q>,
+    storage: &mut dyn Storage,
+    limit: Option<u32>,
+) -> StdResult<Option<bool>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    let mut step = match contract.pending_backfill.may_load(storage)? {
+        Some(step) => step,
+        None => return Ok(None),
+    };
+    let mut budget = limit.unwrap_or(DEFAULT_BACKFILL_LIMIT).max(1) as usize;
+
+    loop {
+        let next = match step {
+            Backfill::SpenderApprovals { start_after } => {
+                let last = index_spender_approvals(contract, storage, start_after, &mut budget)?;
+                match last {
+                    Some(_) if budget == 0 => Backfill::SpenderApprovals { start_after: last },
+                    _ => Backfill::OperatorGrants { start_after: None },
+                }
+            }
+            Backfill::OperatorGrants { start_after } => {
+                let last = index_operator_grants(contract, storage, start_after, &mut budget)?;
+                match last {
+                    Some(_) if budget == 0 => Backfill::OperatorGrants { start_after: last },
+                    _ => Backfill::Balances { start_after: None },
+                }
+            }
+            Backfill::Balances { start_after } => {
+                let last = count_balances(contract, storage, start_after, &mut budget)?;
+                match last {
+                    Some(_) if budget == 0 => Backfill::Balances { start_after: last },
+                    _ => {
+                        contract.pending_backfill.remove(storage);
+                        return Ok(Some(true));
+                    }
+                }
+            }
+        };
+        if budget == 0 {
+            contract.pending_backfill.save(storage, &next)?;
+            return Ok(Some(false));
+        }
+        step = next;
+    }
+}
+
+/// Fills the (spender, token_id) index from the approvals of the tokens after `start_after`.
+/// Returns the last token handled.
+fn index_spender_approvals<T, C, E, Q>(
+    contract: &Cw721Contract<T, C, E, Q>,
+    storage: &mut dyn Storage,
+    start_after: Option<String>,
+    budget: &mut usize,
+) -> StdResult<Option<String>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tokens = contract
+        .tokens
+        .range(storage, start, None, Order::Ascending)
+        .take(*budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    *budget -= tokens.len();
+
+    let last = tokens.last().map(|(token_id, _)| token_id.clone());
+    for (token_id, token) in tokens {
+        for approval in token.approvals {
+            contract.spender_approvals.save(
+                storage,
+                (&approval.spender, &token_id),
+                &approval.expires,
+            )?;
+        }
+    }
+    Ok(last)
+}
+
+/// Fills the (operator, granter) index from the `operators` entries after `start_after`.
+/// Returns the last (granter, operator) pair handled.
+fn index_operator_grants<T, C, E, Q>(
+    contract: &Cw721Contract<T, C, E, Q>,
+    storage: &mut dyn Storage,
+    start_after: Option<(Addr, Addr)>,
+    budget: &mut usize,
+) -> StdResult<Option<(Addr, Addr)>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    let start = start_after
+        .as_ref()
+        .map(|(granter, operator)| Bound::exclusive((granter, operator)));
+    let operators = contract
+        .operators
+        .range(storage, start, None, Order::Ascending)
+        .take(*budget)
+        .collect::<StdResult<Vec<_>>>()?;
+    *budget -= operators.len();
+
+    let last = operators.last().map(|(pair, _)| pair.clone());
+    for ((granter, operator), expires) in operators {
+        contract
+            .operator_grants
+            .save(storage, (&operator, &granter), &expires)?;
+    }
+    Ok(last)
+}
+
+/// Counts the tokens of the owners after `start_after`, overwriting any previous count.
+/// Every counted token uses up the budget, but an owner is always counted as a whole.
+/// Returns the last owner handled.
+fn count_balances<T, C, E, Q>(
+    contract: &Cw721Contract<T, C, E, Q>,
+    storage: &mut dyn Storage,
+    mut start_after: Option<Addr>,
+    budget: &mut usize,
+) -> StdResult<Option<Addr>>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    C: CustomMsg,
+    E: CustomMsg,
+    Q: CustomMsg,
+{
+    let owners = &contract.tokens.idx.owner;
+    while *budget > 0 {
+        // token ids are utf-8 and never contain 0xff, so this skips every token of the owner
+        let min = start_after.as_ref().map(|owner| {
+            let mut key = owners.index_key(owner.clone());
+            key.push(0xff);
+            Bound::InclusiveRaw(key)
+        });
+        let owner = match owners
+            .range_raw(storage, min, None, Order::Ascending)
+            .next()
+        {
+            Some(item) => item?.1.owner,
+            None => break,
+        };
+
+        let balance = owners
+            .prefix(owner.clone())
+            .keys_raw(storage, None, None, Order::Ascending)
+            .count();
+        contract.balances.save(storage, &owner, &(balance as u64))?;
+        *budget = budget.saturating_sub(balance);
+        start_after = Some(owner);
+    }
+    Ok(start_after)
+}
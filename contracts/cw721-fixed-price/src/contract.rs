@@ -1,21 +1,24 @@
 use std::marker::PhantomData;
 
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::msg::{
+    AllowlistResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, ReceiveMsg,
+};
+use crate::state::{Allowlist, Config, ALLOWLIST, CONFIG, PURCHASES};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn, Response,
-    StdResult, SubMsg, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn,
+    Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::Cw20ReceiveMsg;
 use cw721_base::{
     helpers::Cw721Contract, msg::ExecuteMsg as Cw721ExecuteMsg,
     msg::InstantiateMsg as Cw721InstantiateMsg, Extension, MintMsg,
 };
 use cw_utils::parse_reply_instantiate_data;
+use sha2::{Digest, Sha256};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw721-fixed-price";
@@ -62,6 +65,15 @@ pub fn instantiate(
                 name: msg.name.clone(),
                 symbol: msg.symbol,
                 minter: env.contract.address.to_string(),
+                max_supply: Some(msg.max_tokens.into()),
+                track_ownership_history: None,
+                default_limit: None,
+                max_limit: None,
+                token_id_sequence: None,
+                collection_info: None,
+                max_approvals_per_token: None,
+                token_id_rules: None,
+                enable_clawback: None,
             })?,
             funds: vec![],
             admin: None,
@@ -96,13 +108,47 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
     Ok(Response::new())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::WrongContract {
+            previous_contract: stored.contract,
+        });
+    }
+
+    let previous_version: semver::Version = stored.version.parse()?;
+    let new_version: semver::Version = CONTRACT_VERSION.parse()?;
+    if previous_version > new_version {
+        return Err(ContractError::CannotDowngrade {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
+        QueryMsg::GetAllowlist {} => to_binary(&query_allowlist(deps)?),
     }
 }
 
+fn query_allowlist(deps: Deps) -> StdResult<AllowlistResponse> {
+    let allowlist = ALLOWLIST.may_load(deps.storage)?;
+    Ok(AllowlistResponse {
+        merkle_root: allowlist.map(|allowlist| allowlist.merkle_root),
+    })
+}
+
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let config = CONFIG.load(deps.storage)?;
     Ok(ConfigResponse {
@@ -132,7 +178,34 @@ pub fn execute(
             amount,
             msg,
         }) => execute_receive(deps, info, sender, amount, msg),
+        ExecuteMsg::SetAllowlist { merkle_root } => execute_set_allowlist(deps, info, merkle_root),
+    }
+}
+
+pub fn execute_set_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    merkle_root: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match merkle_root {
+        Some(merkle_root) => {
+            if decode_hash(&merkle_root).is_none() {
+                return Err(ContractError::InvalidMerkleRoot {});
+            }
+            let allowlist = Allowlist {
+                merkle_root: merkle_root.to_lowercase(),
+            };
+            ALLOWLIST.save(deps.storage, &allowlist)?;
+        }
+        None => ALLOWLIST.remove(deps.storage),
     }
+
+    Ok(Response::new().add_attribute("action", "set_allowlist"))
 }
 
 pub fn execute_receive(
@@ -140,7 +213,7 @@ pub fn execute_receive(
     info: MessageInfo,
     sender: String,
     amount: Uint128,
-    _msg: Binary,
+    msg: Binary,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     if config.cw20_address != info.sender {
@@ -159,6 +232,21 @@ pub fn execute_receive(
         return Err(ContractError::WrongPaymentAmount {});
     }
 
+    if let Some(allowlist) = ALLOWLIST.may_load(deps.storage)? {
+        let ReceiveMsg { allocation, proof } =
+            from_binary(&msg).map_err(|_| ContractError::InvalidMerkleProof {})?;
+        verify_merkle_proof(&allowlist.merkle_root, &sender, allocation, &proof)?;
+
+        // purchases only count against the allowlist they were made under
+        let buyer = deps.api.addr_validate(&sender)?;
+        let key = (allowlist.merkle_root.as_str(), &buyer);
+        let purchased = PURCHASES.may_load(deps.storage, key)?.unwrap_or_default();
+        if purchased >= allocation {
+            return Err(ContractError::AllocationExceeded {});
+        }
+        PURCHASES.save(deps.storage, key, &(purchased + 1))?;
+    }
+
     let mint_msg = Cw721ExecuteMsg::<Extension, Empty>::Mint(MintMsg::<Extension> {
         token_id: config.unused_token_id.to_string(),
         owner: sender,
@@ -179,6 +267,45 @@ pub fn execute_receive(
     }
 }
 
+/// decodes a hex encoded sha256 hash
+fn decode_hash(hash: &str) -> Option<Vec<u8>> {
+    hex::decode(hash).ok().filter(|bytes| bytes.len() == 32)
+}
+
+/// checks that the address and its allocation are a leaf of the merkle tree with the given root
+fn verify_merkle_proof(
+    merkle_root: &str,
+    address: &str,
+    allocation: u32,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let leaf = Sha256::new()
+        .chain_update(address.as_bytes())
+        .chain_update(allocation.to_be_bytes())
+        .finalize()
+        .to_vec();
+    let hash = proof.iter().try_fold(leaf, |hash, sibling| {
+        let sibling = decode_hash(sibling).ok_or(ContractError::InvalidMerkleProof {})?;
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+        Ok::<_, ContractError>(
+            Sha256::new()
+                .chain_update(first)
+                .chain_update(second)
+                .finalize()
+                .to_vec(),
+        )
+    })?;
+
+    if decode_hash(merkle_root) != Some(hash) {
+        return Err(ContractError::InvalidMerkleProof {});
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,6 +353,15 @@ mod tests {
                         name: msg.name.clone(),
                         symbol: msg.symbol.clone(),
                         minter: MOCK_CONTRACT_ADDR.to_string(),
+                        max_supply: Some(msg.max_tokens.into()),
+                        track_ownership_history: None,
+                        default_limit: None,
+                        max_limit: None,
+                        token_id_sequence: None,
+                        collection_info: None,
+                        max_approvals_per_token: None,
+                        token_id_rules: None,
+                        enable_clawback: None,
                     })
                     .unwrap(),
                     funds: vec![],
@@ -619,6 +755,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn migration() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: Addr::unchecked("owner"),
+            max_tokens: 1,
+            unit_price: Uint128::new(1),
+            name: String::from("SYNTH"),
+            symbol: String::from("SYNTH"),
+            token_code_id: 10u64,
+            cw20_address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            token_uri: String::from("https://ipfs.io/ipfs/Q"),
+            extension: None,
+        };
+
+        let info = mock_info("owner", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // migrating to the same version is fine
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        // cannot migrate from a newer version
+        set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "999.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        match err {
+            ContractError::CannotDowngrade { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // cannot migrate from another contract
+        set_contract_version(
+            deps.as_mut().storage,
+            "crates.io:cw721-base",
+            CONTRACT_VERSION,
+        )
+        .unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        match err {
+            ContractError::WrongContract { previous_contract } => {
+                assert_eq!(previous_contract, "crates.io:cw721-base")
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
     #[test]
     fn wrong_amount() {
         let mut deps = mock_dependencies();
@@ -672,4 +853,202 @@ mod tests {
             e => panic!("unexpected error: {}", e),
         }
     }
+
+    #[test]
+    fn allowlist_sale() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: Addr::unchecked("owner"),
+            max_tokens: 5,
+            unit_price: Uint128::new(1),
+            name: String::from("SYNTH"),
+            symbol: String::from("SYNTH"),
+            token_code_id: 10u64,
+            cw20_address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            token_uri: String::from("https://ipfs.io/ipfs/Q"),
+            extension: None,
+        };
+
+        let info = mock_info("owner", &[]);
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let instantiate_reply = MsgInstantiateContractResponse {
+            contract_address: NFT_CONTRACT_ADDR.to_string(),
+            data: vec![2u8; 32769],
+        };
+        let mut encoded_instantiate_reply =
+            Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+        instantiate_reply
+            .encode(&mut encoded_instantiate_reply)
+            .unwrap();
+
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(encoded_instantiate_reply.into()),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        // merkle tree over four addresses and their allocations
+        let node = |a: &[u8], b: &[u8]| {
+            let (first, second) = if a <= b { (a, b) } else { (b, a) };
+            Sha256::new()
+                .chain_update(first)
+                .chain_update(second)
+                .finalize()
+                .to_vec()
+        };
+        let leaf = |address: &str, allocation: u32| {
+            Sha256::new()
+                .chain_update(address.as_bytes())
+                .chain_update(allocation.to_be_bytes())
+                .finalize()
+                .to_vec()
+        };
+        let leaves = [
+            leaf("alice", 1),
+            leaf("bobby", 2),
+            leaf("carol", 1),
+            leaf("david", 1),
+        ];
+        let left = node(&leaves[0], &leaves[1]);
+        let right = node(&leaves[2], &leaves[3]);
+        let root = hex::encode(node(&left, &right));
+        let alice_proof = vec![hex::encode(&leaves[1]), hex::encode(&right)];
+        let bobby_proof = vec![hex::encode(&leaves[0]), hex::encode(&right)];
+
+        // only the owner can set a valid allowlist
+        let set_allowlist = |merkle_root: Option<String>| ExecuteMsg::SetAllowlist { merkle_root };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            set_allowlist(Some(root.clone())),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let owner = mock_info("owner", &[]);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_allowlist(Some("beef".to_string())),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InvalidMerkleRoot {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_allowlist(Some(root.clone())),
+        )
+        .unwrap();
+        let res: AllowlistResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetAllowlist {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            res,
+            AllowlistResponse {
+                merkle_root: Some(root),
+            }
+        );
+
+        let buy = |sender: &str, allocation: u32, proof: &[String]| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(1),
+                msg: to_binary(&ReceiveMsg {
+                    allocation,
+                    proof: proof.to_vec(),
+                })
+                .unwrap(),
+            })
+        };
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+
+        // buyers must prove they are on the allowlist with their allocation
+        for msg in [
+            buy("eve", 1, &alice_proof),
+            buy("bobby", 1, &alice_proof),
+            buy("alice", 2, &alice_proof),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from("alice"),
+                amount: Uint128::new(1),
+                msg: [].into(),
+            }),
+        ] {
+            let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+            match err {
+                ContractError::InvalidMerkleProof {} => {}
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            buy("alice", 1, &alice_proof),
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // up to their own allocation
+        for _ in 0..2 {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                buy("bobby", 2, &bobby_proof),
+            )
+            .unwrap();
+        }
+        for (sender, allocation, proof) in [("alice", 1, &alice_proof), ("bobby", 2, &bobby_proof)]
+        {
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                buy(sender, allocation, proof),
+            )
+            .unwrap_err();
+            match err {
+                ContractError::AllocationExceeded {} => {}
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+
+        // a new allowlist starts counting purchases from zero
+        let root = hex::encode(node(&leaf("alice", 1), &leaf("carol", 1)));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            set_allowlist(Some(root)),
+        )
+        .unwrap();
+        let proof = vec![hex::encode(leaf("carol", 1))];
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            buy("alice", 1, &proof),
+        )
+        .unwrap();
+
+        // without an allowlist anyone can buy again
+        execute(deps.as_mut(), mock_env(), owner, set_allowlist(None)).unwrap();
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("eve"),
+            amount: Uint128::new(1),
+            msg: [].into(),
+        });
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
 }
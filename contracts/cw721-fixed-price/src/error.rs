@@ -35,4 +35,31 @@ pub enum ContractError {
 
     #[error("Cw721AlreadyLinked")]
     Cw721AlreadyLinked {},
+
+    #[error("InvalidMerkleRoot")]
+    InvalidMerkleRoot {},
+
+    #[error("InvalidMerkleProof")]
+    InvalidMerkleProof {},
+
+    #[error("AllocationExceeded")]
+    AllocationExceeded {},
+
+    #[error("WrongContract")]
+    WrongContract { previous_contract: String },
+
+    #[error("CannotDowngrade")]
+    CannotDowngrade {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("SemVer")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
@@ -16,9 +16,29 @@ pub struct InstantiateMsg {
     pub extension: Extension,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    /// Restricts sales to the addresses in the merkle tree, or opens them to anyone if
+    /// `merkle_root` is unset. Can only be called by the owner
+    SetAllowlist {
+        /// Hex encoded sha256 merkle root. Leaves are the hash of an address followed by
+        /// its allocation as a big endian u32, inner nodes the hash of both children in
+        /// ascending order
+        merkle_root: Option<String>,
+    },
+}
+
+/// Sent as `Cw20ReceiveMsg.msg` to buy while an allowlist is set
+#[cw_serde]
+pub struct ReceiveMsg {
+    /// How many tokens the buyer can buy, as committed in their leaf
+    pub allocation: u32,
+    /// Hex encoded sibling hashes from the buyer's leaf up to the root
+    pub proof: Vec<String>,
 }
 
 #[cw_serde]
@@ -26,6 +46,13 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     GetConfig {},
+    #[returns(AllowlistResponse)]
+    GetAllowlist {},
+}
+
+#[cw_serde]
+pub struct AllowlistResponse {
+    pub merkle_root: Option<String>,
 }
 
 #[cw_serde]
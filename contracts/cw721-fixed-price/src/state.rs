@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 use cw721_base::Extension;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
@@ -18,3 +18,13 @@ pub struct Config {
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+
+#[cw_serde]
+pub struct Allowlist {
+    /// Hex encoded sha256 merkle root of the allowed addresses and their allocations
+    pub merkle_root: String,
+}
+
+pub const ALLOWLIST: Item<Allowlist> = Item::new("allowlist");
+/// Number of tokens each address bought, keyed by the merkle root they bought under
+pub const PURCHASES: Map<(&str, &Addr), u32> = Map::new("purchases");
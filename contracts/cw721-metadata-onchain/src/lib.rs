@@ -1,7 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Empty;
 use cw2::set_contract_version;
-pub use cw721_base::{ContractError, InstantiateMsg, MintMsg, MinterResponse};
+pub use cw721_base::{ContractError, InstantiateMsg, MigrateMsg, MintMsg, MinterResponse};
 
 // Version info for migration
 const CONTRACT_NAME: &str = "crates.io:cw721-metadata-onchain";
@@ -71,6 +71,11 @@ pub mod entry {
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         Cw721MetadataContract::default().query(deps, env, msg)
     }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        Cw721MetadataContract::default().migrate(deps, env, msg, CONTRACT_NAME, CONTRACT_VERSION)
+    }
 }
 
 #[cfg(test)]
@@ -92,6 +97,15 @@ mod tests {
             name: "SpaceShips".to_string(),
             symbol: "SPACE".to_string(),
             minter: CREATOR.to_string(),
+            max_supply: None,
+            track_ownership_history: None,
+            default_limit: None,
+            max_limit: None,
+            token_id_sequence: None,
+            collection_info: None,
+            max_approvals_per_token: None,
+            token_id_rules: None,
+            enable_clawback: None,
         };
         contract
             .instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg)
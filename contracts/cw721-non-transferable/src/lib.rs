@@ -3,7 +3,7 @@ use cosmwasm_std::Empty;
 pub use cw721_base::{
     entry::{execute as _execute, query as _query},
     ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg as Cw721BaseInstantiateMsg,
-    MintMsg, MinterResponse,
+    MigrateMsg, MintMsg, MinterResponse,
 };
 
 pub mod msg;
@@ -46,6 +46,15 @@ pub mod entry {
             name: msg.name,
             symbol: msg.symbol,
             minter: msg.minter,
+            max_supply: msg.max_supply,
+            track_ownership_history: msg.track_ownership_history,
+            default_limit: msg.default_limit,
+            max_limit: msg.max_limit,
+            token_id_sequence: msg.token_id_sequence,
+            collection_info: msg.collection_info,
+            max_approvals_per_token: msg.max_approvals_per_token,
+            token_id_rules: msg.token_id_rules,
+            enable_clawback: msg.enable_clawback,
         };
 
         Cw721NonTransferableContract::default().instantiate(
@@ -82,6 +91,15 @@ pub mod entry {
                 ExecuteMsg::Mint(msg) => {
                     Cw721NonTransferableContract::default().mint(deps, env, info, msg)
                 }
+                ExecuteMsg::BatchMint { mints } => {
+                    Cw721NonTransferableContract::default().batch_mint(deps, env, info, mints)
+                }
+                ExecuteMsg::MintNext {
+                    owner,
+                    token_uri,
+                    extension,
+                } => Cw721NonTransferableContract::default()
+                    .mint_next(deps, env, info, owner, token_uri, extension),
                 _ => Err(ContractError::Unauthorized {}),
             },
         }
@@ -94,4 +112,15 @@ pub mod entry {
             _ => _query(deps, env, msg.into()),
         }
     }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        Cw721NonTransferableContract::default().migrate(
+            deps,
+            env,
+            msg,
+            CONTRACT_NAME,
+            CONTRACT_VERSION,
+        )
+    }
 }
@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Empty;
 use cw721_base::msg::QueryMsg as Cw721QueryMsg;
+use cw721_base::{CollectionInfo, TokenIdRules, TokenIdSequence};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -8,6 +9,15 @@ pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub minter: String,
+    pub max_supply: Option<u64>,
+    pub track_ownership_history: Option<bool>,
+    pub default_limit: Option<u32>,
+    pub max_limit: Option<u32>,
+    pub token_id_sequence: Option<TokenIdSequence>,
+    pub collection_info: Option<CollectionInfo>,
+    pub max_approvals_per_token: Option<u32>,
+    pub token_id_rules: Option<TokenIdRules>,
+    pub enable_clawback: Option<bool>,
 }
 
 #[cw_serde]
@@ -80,11 +90,16 @@ impl From<QueryMsg> for Cw721QueryMsg<Empty> {
             } => Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
+                start_before: None,
+                order: None,
+                limit,
+            },
+            QueryMsg::AllTokens { start_after, limit } => Cw721QueryMsg::AllTokens {
+                start_after,
+                start_before: None,
+                order: None,
                 limit,
             },
-            QueryMsg::AllTokens { start_after, limit } => {
-                Cw721QueryMsg::AllTokens { start_after, limit }
-            }
             QueryMsg::Minter {} => Cw721QueryMsg::Minter {},
             _ => unreachable!("cannot convert {:?} to Cw721QueryMsg", msg),
         }